@@ -2,6 +2,17 @@
 
 //! Extensions for options and results.
 
+use std::borrow::Cow;
+use std::future::Future;
+
+mod context;
+mod error;
+mod validated;
+
+pub use context::*;
+pub use error::{Chain, Error, Report};
+pub use validated::*;
+
 mod sealed {
 	pub trait SealedOptionExt { }
 	pub trait SealedResultExt { }
@@ -28,15 +39,24 @@ pub trait OptionExt<T>: sealed::SealedOptionExt {
 	fn populate_default(&mut self) where T: Default {
 		self.populate_with(T::default)
 	}
+	/// Inserts a value computed by the fallible `f` into the option if it is
+	/// [`None`], returning any error. The option is left [`None`] on error.
+	fn try_populate_with<E>(&mut self, f: impl FnOnce() -> Result<T, E>) -> Result<(), E>;
 
 	/// Maps the option's contained value into type `R` implementing the [`From`]
 	/// trait. Shorthand for `map(Into::into)`.
 	fn map_into<R: From<T>>(self) -> Option<R>;
+	/// Maps the option's contained value into type `R` implementing the
+	/// [`TryFrom`] trait, returning any conversion error.
+	fn try_map_into<R: TryFrom<T>>(self) -> Result<Option<R>, R::Error>;
 	/// Maps the option's contained value into a string. Shorthand for
 	/// `as_ref().map(ToString::to_string)`.
 	fn map_to_string(self) -> Option<String> where T: ToString;
 	/// Updates the option's contained value with an `update` closure.
 	fn update<R>(&mut self, update: impl FnOnce(&mut T) -> R) -> Option<R>;
+	/// Updates the option's contained value with a fallible `update` closure,
+	/// returning any error.
+	fn try_update<R, E>(&mut self, update: impl FnOnce(&mut T) -> Result<R, E>) -> Result<Option<R>, E>;
 	/// Returns [`None`] if the option is [`None`], otherwise calls predicate with
 	/// the wrapped value and returns:
 	///
@@ -49,32 +69,69 @@ pub trait OptionExt<T>: sealed::SealedOptionExt {
 	///
 	/// [`filter`]: Option::filter
 	fn try_filter<E>(self, predicate: impl FnOnce(&T) -> Result<bool, E>) -> Result<Option<T>, E>;
+	/// Transforms the option into a [`Result`], mapping [`None`] to a [`NoneError`]
+	/// wrapped with a `context` message.
+	fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Contextual<NoneError>>;
+	/// Transforms the option into a [`Result`], mapping [`None`] to a [`NoneError`]
+	/// wrapped with a context message computed by `f`.
+	fn with_context<C: Into<Cow<'static, str>>>(self, f: impl FnOnce() -> C) -> Result<T, Contextual<NoneError>>;
+
+	/// Inserts a value computed by the future `f` returns into the option if it
+	/// is [`None`]. Async version of [`populate_with`](OptionExt::populate_with).
+	fn populate_with_async<F: Future<Output = T>>(&mut self, f: impl FnOnce() -> F) -> impl Future<Output = ()>;
+	/// Filters the option with an async `predicate`. Async version of
+	/// [`try_filter`](OptionExt::try_filter).
+	fn try_filter_async<E, F: Future<Output = Result<bool, E>>>(
+		self,
+		predicate: impl FnOnce(&T) -> F
+	) -> impl Future<Output = Result<Option<T>, E>>;
+	/// Maps the option's contained value with an async function. Async version of
+	/// [`map`](Option::map).
+	fn map_async<U, F: Future<Output = U>>(self, f: impl FnOnce(T) -> F) -> impl Future<Output = Option<U>>;
+	/// Returns [`None`] if the option is [`None`], otherwise awaits `f` with the
+	/// contained value. Async version of [`and_then`](Option::and_then).
+	fn and_then_async<U, F: Future<Output = Option<U>>>(self, f: impl FnOnce(T) -> F) -> impl Future<Output = Option<U>>;
+	/// Updates the option's contained value with an async `update` closure. Async
+	/// version of [`update`](OptionExt::update).
+	fn update_async<R>(&mut self, update: impl AsyncFnOnce(&mut T) -> R) -> impl Future<Output = Option<R>>;
 }
 
 impl<T> OptionExt<T> for Option<T> {
 	fn populate(&mut self, value: T) {
-		if let None = *self {
+		if self.is_none() {
 			*self = Some(value);
 		}
 	}
 
 	fn populate_with(&mut self, f: impl FnOnce() -> T) {
-		if let None = *self {
+		if self.is_none() {
 			*self = Some(f());
 		}
 	}
 
+	fn try_populate_with<E>(&mut self, f: impl FnOnce() -> Result<T, E>) -> Result<(), E> {
+		if self.is_none() {
+			*self = Some(f()?);
+		}
+		Ok(())
+	}
+
 	fn map_into<R: From<T>>(self) -> Option<R> { self.map(R::from) }
 
+	fn try_map_into<R: TryFrom<T>>(self) -> Result<Option<R>, R::Error> {
+		self.map(R::try_from).transpose()
+	}
+
 	fn map_to_string(self) -> Option<String> where T: ToString {
 		Some(self?.to_string())
 	}
 
 	fn update<R>(&mut self, update: impl FnOnce(&mut T) -> R) -> Option<R> {
-		match self {
-			Some(v) => Some(update(v)),
-			None => None
-		}
+		self.as_mut().map(update)
+	}
+
+	fn try_update<R, E>(&mut self, update: impl FnOnce(&mut T) -> Result<R, E>) -> Result<Option<R>, E> {
+		self.as_mut().map(update).transpose()
 	}
 
 	fn try_filter<E>(self, predicate: impl FnOnce(&T) -> Result<bool, E>) -> Result<Option<T>, E> {
@@ -83,6 +140,48 @@ impl<T> OptionExt<T> for Option<T> {
 			_ => Ok(None)
 		}
 	}
+
+	fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Contextual<NoneError>> {
+		self.ok_or_else(|| Contextual::new(context, NoneError))
+	}
+
+	fn with_context<C: Into<Cow<'static, str>>>(self, f: impl FnOnce() -> C) -> Result<T, Contextual<NoneError>> {
+		self.ok_or_else(|| Contextual::new(f(), NoneError))
+	}
+
+	async fn populate_with_async<F: Future<Output = T>>(&mut self, f: impl FnOnce() -> F) {
+		if self.is_none() {
+			*self = Some(f().await);
+		}
+	}
+
+	async fn try_filter_async<E, F: Future<Output = Result<bool, E>>>(
+		self,
+		predicate: impl FnOnce(&T) -> F
+	) -> Result<Option<T>, E> {
+		match self {
+			Some(value) if predicate(&value).await? => Ok(Some(value)),
+			_ => Ok(None)
+		}
+	}
+
+	async fn map_async<U, F: Future<Output = U>>(self, f: impl FnOnce(T) -> F) -> Option<U> {
+		match self {
+			Some(value) => Some(f(value).await),
+			None => None
+		}
+	}
+
+	async fn and_then_async<U, F: Future<Output = Option<U>>>(self, f: impl FnOnce(T) -> F) -> Option<U> {
+		f(self?).await
+	}
+
+	async fn update_async<R>(&mut self, update: impl AsyncFnOnce(&mut T) -> R) -> Option<R> {
+		match self {
+			Some(value) => Some(update(value).await),
+			None => None
+		}
+	}
 }
 
 pub trait ResultExt<T, E>: sealed::SealedResultExt {
@@ -116,12 +215,37 @@ pub trait ResultExt<T, E>: sealed::SealedResultExt {
 	/// Maps a contained [`Ok`] value into type `R` implementing the [`From`] trait.
 	/// Shorthand for `map(Into::into)`.
 	fn map_into<R: From<T>>(self) -> Result<R, E>;
+	/// Maps a contained [`Ok`] value into type `R` implementing the [`TryFrom`]
+	/// trait, converting any conversion error into `E`.
+	fn try_map_into<R: TryFrom<T>>(self) -> Result<R, E> where E: From<R::Error>;
 	/// Maps a contained [`Ok`] value into a string.
 	fn map_to_string(self) -> Result<String, E> where T: ToString;
 	/// Updates a contained [`Ok`] value with an `update` closure.
 	fn update<R>(&mut self, update: impl FnOnce(&mut T) -> R) -> Option<R>;
 	/// Updates a contained [`Err`] value with an `update` closure.
 	fn update_err<R>(&mut self, update: impl FnOnce(&mut E) -> R) -> Option<R>;
+	/// Wraps a contained [`Err`] value with a `context` message describing what
+	/// was being done. The original error becomes the [`source`][] of the
+	/// [`Contextual`] error.
+	///
+	/// [`source`]: std::error::Error::source
+	fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Contextual<E>>;
+	/// Wraps a contained [`Err`] value with a context message computed by `f`,
+	/// which is only called on error. See [`context`](ResultExt::context).
+	fn with_context<C: Into<Cow<'static, str>>>(self, f: impl FnOnce() -> C) -> Result<T, Contextual<E>>;
+
+	/// Maps a contained [`Ok`] value with an async function. Async version of
+	/// [`map`](Result::map).
+	fn map_async<U, F: Future<Output = U>>(self, f: impl FnOnce(T) -> F) -> impl Future<Output = Result<U, E>>;
+	/// Maps a contained [`Err`] value with an async function. Async version of
+	/// [`map_err`](Result::map_err).
+	fn map_err_async<R, F: Future<Output = R>>(self, f: impl FnOnce(E) -> F) -> impl Future<Output = Result<T, R>>;
+	/// Returns a contained [`Err`] value, otherwise awaits `f` with the [`Ok`]
+	/// value. Async version of [`and_then`](Result::and_then).
+	fn and_then_async<U, F: Future<Output = Result<U, E>>>(self, f: impl FnOnce(T) -> F) -> impl Future<Output = Result<U, E>>;
+	/// Updates a contained [`Ok`] value with an async `update` closure. Async
+	/// version of [`update`](ResultExt::update).
+	fn update_async<R>(&mut self, update: impl AsyncFnOnce(&mut T) -> R) -> impl Future<Output = Option<R>>;
 }
 
 impl<T, E> ResultExt<T, E> for Result<T, E> {
@@ -134,6 +258,10 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
 
 	fn map_into<R: From<T>>(self) -> Result<R, E> { self.map(R::from) }
 
+	fn try_map_into<R: TryFrom<T>>(self) -> Result<R, E> where E: From<R::Error> {
+		Ok(R::try_from(self?)?)
+	}
+
 	fn map_to_string(self) -> Result<String, E> where T: ToString {
 		Ok(self?.to_string())
 	}
@@ -151,4 +279,187 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
 			_ => None
 		}
 	}
+
+	fn context(self, context: impl Into<Cow<'static, str>>) -> Result<T, Contextual<E>> {
+		self.map_err(|error| Contextual::new(context, error))
+	}
+
+	fn with_context<C: Into<Cow<'static, str>>>(self, f: impl FnOnce() -> C) -> Result<T, Contextual<E>> {
+		self.map_err(|error| Contextual::new(f(), error))
+	}
+
+	async fn map_async<U, F: Future<Output = U>>(self, f: impl FnOnce(T) -> F) -> Result<U, E> {
+		Ok(f(self?).await)
+	}
+
+	async fn map_err_async<R, F: Future<Output = R>>(self, f: impl FnOnce(E) -> F) -> Result<T, R> {
+		match self {
+			Ok(value) => Ok(value),
+			Err(error) => Err(f(error).await)
+		}
+	}
+
+	async fn and_then_async<U, F: Future<Output = Result<U, E>>>(self, f: impl FnOnce(T) -> F) -> Result<U, E> {
+		f(self?).await
+	}
+
+	async fn update_async<R>(&mut self, update: impl AsyncFnOnce(&mut T) -> R) -> Option<R> {
+		match self {
+			Ok(value) => Some(update(value).await),
+			Err(_) => None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::future::Future;
+	use std::pin::pin;
+	use std::sync::Arc;
+	use std::task::{Context, Poll, Wake};
+	use std::thread::{self, Thread};
+	use super::{OptionExt, ResultExt};
+
+	/// Wakes the executor by unparking its thread.
+	struct ThreadWaker(Thread);
+
+	impl Wake for ThreadWaker {
+		fn wake(self: Arc<Self>) { self.0.unpark() }
+	}
+
+	/// A minimal executor, polling `future` on the current thread until it
+	/// completes.
+	fn block_on<F: Future>(future: F) -> F::Output {
+		let mut future = pin!(future);
+		let waker = Arc::new(ThreadWaker(thread::current())).into();
+		let mut context = Context::from_waker(&waker);
+		loop {
+			match future.as_mut().poll(&mut context) {
+				Poll::Ready(output) => return output,
+				Poll::Pending => thread::park()
+			}
+		}
+	}
+
+	/// Returns `value` after yielding to the executor once.
+	async fn yielded<T>(value: T) -> T {
+		let mut yielded = false;
+		std::future::poll_fn(|context| {
+			if yielded {
+				return Poll::Ready(())
+			}
+
+			yielded = true;
+			context.waker().wake_by_ref();
+			Poll::Pending
+		}).await;
+		value
+	}
+
+	#[test]
+	fn populate_with_async() {
+		let mut option = None;
+		block_on(option.populate_with_async(|| yielded(1)));
+		assert_eq!(option, Some(1));
+		block_on(option.populate_with_async(|| async { unreachable!() }));
+		assert_eq!(option, Some(1));
+	}
+
+	#[test]
+	fn try_filter_async() {
+		let even = |value: &i32| yielded(Ok::<_, ()>(value % 2 == 0));
+		assert_eq!(block_on(Some(2).try_filter_async(even)), Ok(Some(2)));
+		assert_eq!(block_on(Some(3).try_filter_async(even)), Ok(None));
+		assert_eq!(block_on(None.try_filter_async(even)), Ok(None));
+		assert_eq!(block_on(Some(2).try_filter_async(|_| yielded(Err("failed")))), Err("failed"));
+	}
+
+	#[test]
+	fn option_map_async() {
+		assert_eq!(block_on(Some(2).map_async(|value| yielded(value * 3))), Some(6));
+		assert_eq!(block_on(None::<i32>.map_async(|value| yielded(value * 3))), None);
+	}
+
+	#[test]
+	fn option_and_then_async() {
+		let half = |value: i32| yielded((value % 2 == 0).then_some(value / 2));
+		assert_eq!(block_on(Some(4).and_then_async(half)), Some(2));
+		assert_eq!(block_on(Some(3).and_then_async(half)), None);
+		assert_eq!(block_on(None.and_then_async(half)), None);
+	}
+
+	#[test]
+	fn result_map_async() {
+		assert_eq!(block_on(Ok::<_, ()>(2).map_async(|value| yielded(value + 1))), Ok(3));
+		assert_eq!(block_on(Err::<i32, _>("failed").map_async(|value| yielded(value + 1))), Err("failed"));
+		assert_eq!(block_on(Err::<(), _>(2).map_err_async(|error| yielded(error * 2))), Err(4));
+		assert_eq!(block_on(Ok::<_, i32>(1).map_err_async(|error| yielded(error * 2))), Ok(1));
+	}
+
+	#[test]
+	fn result_and_then_async() {
+		let parse = |value: &'static str| yielded(value.parse::<u8>().map_err(|_| "invalid"));
+		assert_eq!(block_on(Ok("7").and_then_async(parse)), Ok(7));
+		assert_eq!(block_on(Ok("x").and_then_async(parse)), Err("invalid"));
+		assert_eq!(block_on(Err("failed").and_then_async(parse)), Err("failed"));
+	}
+
+	#[test]
+	fn option_update_async() {
+		let mut option = Some(1);
+		let add = async |value: &mut i32| {
+			*value += yielded(2).await;
+			*value
+		};
+		assert_eq!(block_on(option.update_async(add)), Some(3));
+		assert_eq!(option, Some(3));
+		assert_eq!(block_on(None.update_async(add)), None);
+	}
+
+	#[test]
+	fn result_update_async() {
+		let mut result = Ok::<_, &str>(1);
+		let add = async |value: &mut i32| {
+			*value += yielded(2).await;
+			*value
+		};
+		assert_eq!(block_on(result.update_async(add)), Some(3));
+		assert_eq!(result, Ok(3));
+		let mut result = Err::<i32, _>("failed");
+		assert_eq!(block_on(result.update_async(add)), None);
+		assert_eq!(result, Err("failed"));
+	}
+
+	#[test]
+	fn try_populate_with() {
+		let mut option = None;
+		assert_eq!(option.try_populate_with(|| Err("failed")), Err("failed"));
+		assert_eq!(option, None);
+		assert_eq!(option.try_populate_with(|| Ok::<_, ()>(1)), Ok(()));
+		assert_eq!(option.try_populate_with(|| Err("unreachable")), Ok(()));
+		assert_eq!(option, Some(1));
+	}
+
+	#[test]
+	fn try_update() {
+		let mut option = Some(1);
+		assert_eq!(option.try_update(|value| { *value += 1; Ok::<_, ()>(*value) }), Ok(Some(2)));
+		assert_eq!(option.try_update(|_| Err::<(), _>("failed")), Err("failed"));
+		assert_eq!(None::<i32>.try_update(|_| Err::<(), _>("failed")), Ok(None));
+	}
+
+	#[test]
+	fn try_map_into() {
+		assert_eq!(Some(1i32).try_map_into::<u8>(), Ok(Some(1)));
+		assert!(Some(-1i32).try_map_into::<u8>().is_err());
+		assert_eq!(None::<i32>.try_map_into::<u8>(), Ok(None));
+
+		#[derive(Debug, PartialEq)]
+		struct Failed;
+		impl From<std::num::TryFromIntError> for Failed {
+			fn from(_: std::num::TryFromIntError) -> Self { Failed }
+		}
+		assert_eq!(Ok::<_, Failed>(1i32).try_map_into::<u8>(), Ok(1));
+		assert_eq!(Ok::<_, Failed>(-1i32).try_map_into::<u8>(), Err(Failed));
+	}
 }
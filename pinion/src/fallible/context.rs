@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Errors annotated with context, created by [`ResultExt::context`] and
+//! [`OptionExt::context`].
+//!
+//! [`ResultExt::context`]: super::ResultExt::context
+//! [`OptionExt::context`]: super::OptionExt::context
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// An error wrapped with a message describing what was being done when it
+/// occurred. Displays only the message; the wrapped error is its [`source`][], so
+/// the full chain can be walked or reported.
+///
+/// [`source`]: Error::source
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Contextual<E> {
+	context: Cow<'static, str>,
+	error: E
+}
+
+impl<E> Contextual<E> {
+	/// Wraps `error` with a `context` message.
+	pub fn new(context: impl Into<Cow<'static, str>>, error: E) -> Self {
+		Self { context: context.into(), error }
+	}
+
+	/// Returns the context message.
+	pub fn context(&self) -> &str { &self.context }
+
+	/// Returns a reference to the wrapped error.
+	pub fn get_ref(&self) -> &E { &self.error }
+
+	/// Unwraps the error, discarding the context.
+	pub fn into_inner(self) -> E { self.error }
+}
+
+impl<E> Display for Contextual<E> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(&self.context)
+	}
+}
+
+impl<E: Error + 'static> Error for Contextual<E> {
+	fn source(&self) -> Option<&(dyn Error + 'static)> { Some(&self.error) }
+}
+
+/// The error wrapped by [`Contextual`] when an [`Option`] is [`None`], created by
+/// [`OptionExt::context`].
+///
+/// [`OptionExt::context`]: super::OptionExt::context
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NoneError;
+
+impl Display for NoneError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str("no value present")
+	}
+}
+
+impl Error for NoneError { }
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+	use std::fmt;
+	use super::*;
+	use crate::{OptionExt, ResultExt};
+
+	#[derive(Debug, PartialEq)]
+	struct Failed;
+
+	impl Display for Failed {
+		fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str("failed") }
+	}
+
+	impl Error for Failed { }
+
+	#[test]
+	fn contextual() {
+		let error = Contextual::new("reading config", Failed);
+		assert_eq!(error.to_string(), "reading config");
+		assert_eq!(error.context(), "reading config");
+		assert_eq!(error.get_ref(), &Failed);
+		assert_eq!(error.source().unwrap().to_string(), "failed");
+		assert!(error.source().unwrap().is::<Failed>());
+		assert_eq!(error.into_inner(), Failed);
+
+		let owned = Contextual::new(format!("reading {}", "config.toml"), Failed);
+		assert_eq!(owned.to_string(), "reading config.toml");
+	}
+
+	#[test]
+	fn nested_contextual() {
+		let error = Contextual::new("starting", Contextual::new("reading config", Failed));
+		let root: &(dyn Error + 'static) = &error;
+		let chain: Vec<_> = std::iter::successors(Some(root), |&error| error.source())
+			.map(ToString::to_string)
+			.collect();
+		assert_eq!(chain, ["starting", "reading config", "failed"]);
+	}
+
+	#[test]
+	fn none_error() {
+		assert_eq!(NoneError.to_string(), "no value present");
+		assert!(NoneError.source().is_none());
+	}
+
+	#[test]
+	fn option_context() {
+		assert_eq!(Some(1).context("missing"), Ok(1));
+		let error = None::<i32>.context("missing value").unwrap_err();
+		assert_eq!(error.to_string(), "missing value");
+		assert_eq!(error.get_ref(), &NoneError);
+		assert!(error.source().unwrap().is::<NoneError>());
+	}
+
+	#[test]
+	fn option_with_context() {
+		let called = Cell::new(false);
+		let context = || {
+			called.set(true);
+			format!("missing {}", 1)
+		};
+		assert_eq!(Some(1).with_context(context), Ok(1));
+		assert!(!called.get());
+		let error = None::<i32>.with_context(context).unwrap_err();
+		assert!(called.get());
+		assert_eq!(error.to_string(), "missing 1");
+		assert_eq!(error.into_inner(), NoneError);
+	}
+
+	#[test]
+	fn result_context() {
+		assert_eq!(Ok::<_, Failed>(1).context("loading"), Ok(1));
+		let error = Err::<(), _>(Failed).context("loading").unwrap_err();
+		assert_eq!(error.to_string(), "loading");
+		assert_eq!(error.source().unwrap().to_string(), "failed");
+		assert_eq!(error.into_inner(), Failed);
+	}
+
+	#[test]
+	fn result_with_context() {
+		let called = Cell::new(false);
+		let context = || {
+			called.set(true);
+			"loading"
+		};
+		assert_eq!(Ok::<_, Failed>(1).with_context(context), Ok(1));
+		assert!(!called.get());
+		let error = Err::<(), _>(Failed).with_context(context).unwrap_err();
+		assert!(called.get());
+		assert_eq!(error.context(), "loading");
+		assert!(error.source().unwrap().is::<Failed>());
+	}
+}
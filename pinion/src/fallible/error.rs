@@ -0,0 +1,425 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A boxed dynamic error type for applications, with context, attachments and
+//! an optional backtrace.
+
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::iter::FusedIterator;
+use std::mem::size_of;
+use super::Contextual;
+
+/// A dynamic error wrapping any [`std::error::Error`], the size of a single
+/// pointer. On top of the wrapped error, it carries a stack of context messages,
+/// typed attachments, and a backtrace captured on creation if enabled by the
+/// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables.
+///
+/// Any error converts into this one with `?`. Context added to a result of this
+/// error with [`ResultExt::context`][] is folded back into the stack when
+/// converted, so `result.context("...")?` works here too; other errors keep their
+/// context in the chain, see [`contexts`](Error::contexts). It displays its
+/// outermost context message, or the wrapped error without context; [`report`][]
+/// formats the full chain, as does [`Debug`] so it prints well when returned from
+/// `main`.
+///
+/// [`ResultExt::context`]: super::ResultExt::context
+/// [`report`]: Error::report
+pub struct Error {
+	inner: Box<Inner>
+}
+
+struct Inner {
+	error: Box<dyn StdError + Send + Sync>,
+	/// Context messages, innermost first.
+	context: Vec<Cow<'static, str>>,
+	attachments: Vec<Box<dyn Any + Send + Sync>>,
+	backtrace: Backtrace
+}
+
+const _: () = assert!(size_of::<Error>() == size_of::<usize>());
+
+impl Error {
+	/// Wraps `error`, capturing a backtrace if enabled.
+	pub fn new<E: StdError + Send + Sync + 'static>(error: E) -> Self {
+		Self::from_boxed(Box::new(error))
+	}
+
+	/// Creates an error from a `message`.
+	pub fn msg(message: impl Into<Cow<'static, str>>) -> Self {
+		Self::new(Message(message.into()))
+	}
+
+	/// Wraps an already boxed `error`, capturing a backtrace if enabled.
+	pub fn from_boxed(error: Box<dyn StdError + Send + Sync>) -> Self {
+		Self {
+			inner: Box::new(Inner {
+				error,
+				context: Vec::new(),
+				attachments: Vec::new(),
+				backtrace: Backtrace::capture()
+			})
+		}
+	}
+
+	/// Adds a `context` message describing what was being done when the error
+	/// occurred.
+	pub fn context(mut self, context: impl Into<Cow<'static, str>>) -> Self {
+		self.inner.context.push(context.into());
+		self
+	}
+
+	/// Attaches a value of any type, retrievable with [`attachment`][].
+	///
+	/// [`attachment`]: Error::attachment
+	pub fn attach<A: Any + Send + Sync>(mut self, attachment: A) -> Self {
+		self.inner.attachments.push(Box::new(attachment));
+		self
+	}
+
+	/// Returns the most recently attached value of type `A`, if any.
+	pub fn attachment<A: Any>(&self) -> Option<&A> {
+		self.inner.attachments
+			.iter()
+			.rev()
+			.find_map(|attachment| attachment.downcast_ref())
+	}
+
+	/// Returns the context messages, outermost first. These are the messages added
+	/// with [`context`](Error::context) or folded in from a [`Contextual`] wrapping
+	/// an `Error`. A `Contextual` wrapping any other error, such as one from
+	/// [`ResultExt::context`][] on an `io::Result`, converts like every other
+	/// error: it becomes the wrapped error, so its message shows in the
+	/// [`chain`][] and [`report`][] but not here. Folding those too would need a
+	/// conversion overlapping the one from every error type.
+	///
+	/// [`ResultExt::context`]: super::ResultExt::context
+	/// [`chain`]: Error::chain
+	/// [`report`]: Error::report
+	pub fn contexts(&self) -> impl DoubleEndedIterator<Item = &str> + ExactSizeIterator {
+		self.inner.context.iter().rev().map(AsRef::as_ref)
+	}
+
+	/// Returns the backtrace captured with the error, if one was.
+	pub fn backtrace(&self) -> Option<&Backtrace> {
+		let backtrace = &self.inner.backtrace;
+		(backtrace.status() == BacktraceStatus::Captured).then_some(backtrace)
+	}
+
+	/// Returns the first error of type `E` in the [`chain`][], if any.
+	///
+	/// [`chain`]: Error::chain
+	pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+		self.chain().find_map(|error| error.downcast_ref())
+	}
+
+	/// Unwraps the wrapped error if it is of type `E`, discarding context,
+	/// attachments and the backtrace, or returns this error otherwise.
+	pub fn downcast<E: StdError + 'static>(self) -> Result<E, Self> {
+		if !self.inner.error.is::<E>() {
+			return Err(self)
+		}
+
+		Ok(*self.inner.error.downcast().expect("the error should be of type E"))
+	}
+
+	/// Returns an iterator over the wrapped error and its [`source`][]s, from
+	/// outermost to innermost. Context messages aren't included.
+	///
+	/// [`source`]: StdError::source
+	pub fn chain(&self) -> Chain<'_> {
+		Chain { next: Some(&*self.inner.error) }
+	}
+
+	/// Returns a formatter for the full error chain: every context message, then
+	/// the wrapped error and its sources, followed by the backtrace if captured.
+	pub fn report(&self) -> Report<'_> {
+		Report(self)
+	}
+}
+
+impl<E: StdError + Send + Sync + 'static> From<E> for Error {
+	fn from(error: E) -> Self { Self::new(error) }
+}
+
+impl From<Contextual<Error>> for Error {
+	fn from(error: Contextual<Error>) -> Self {
+		let context = error.context().to_owned();
+		error.into_inner().context(context)
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self.inner.context.last() {
+			Some(context) => f.write_str(context),
+			None => Display::fmt(&self.inner.error, f)
+		}
+	}
+}
+
+impl Debug for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		Display::fmt(&self.report(), f)
+	}
+}
+
+/// An iterator over an [`Error`]'s wrapped error and its sources, created by
+/// [`Error::chain`].
+#[derive(Clone, Debug)]
+pub struct Chain<'a> {
+	next: Option<&'a (dyn StdError + 'static)>
+}
+
+impl<'a> Iterator for Chain<'a> {
+	type Item = &'a (dyn StdError + 'static);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let error = self.next?;
+		self.next = error.source();
+		Some(error)
+	}
+}
+
+impl FusedIterator for Chain<'_> { }
+
+/// Displays an [`Error`]'s full chain, created by [`Error::report`]:
+///
+/// ```text
+/// loading config
+///
+/// Caused by:
+///     0: reading "app.toml"
+///     1: No such file or directory (os error 2)
+/// ```
+#[derive(Copy, Clone)]
+pub struct Report<'a>(&'a Error);
+
+impl Display for Report<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let error = self.0;
+		let contexts = error.inner.context.iter().rev().map(|context| context as &dyn Display);
+		let chain = error.chain().map(|error| error as &dyn Display);
+		let mut messages = contexts.chain(chain);
+		if let Some(message) = messages.next() {
+			write!(f, "{message}")?;
+		}
+
+		for (i, cause) in messages.enumerate() {
+			if i == 0 {
+				f.write_str("\n\nCaused by:")?;
+			}
+			write!(f, "\n    {i}: {cause}")?;
+		}
+
+		if let Some(backtrace) = error.backtrace() {
+			write!(f, "\n\nStack backtrace:\n{backtrace}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Debug for Report<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { Display::fmt(self, f) }
+}
+
+/// An error with only a message, created by [`Error::msg`].
+struct Message(Cow<'static, str>);
+
+impl Display for Message {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+}
+
+impl Debug for Message {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { Debug::fmt(&self.0, f) }
+}
+
+impl StdError for Message { }
+
+/// Returns early with an [`Error`]. Takes either a format string and arguments,
+/// like [`format!`], or any error value:
+///
+/// ```
+/// # use pinion_rs::{bail, Error};
+/// fn check(port: u16) -> Result<(), Error> {
+/// 	if port < 1024 {
+/// 		bail!("port {port} is reserved");
+/// 	}
+/// 	Ok(())
+/// }
+/// # assert!(check(80).is_err());
+/// ```
+#[macro_export]
+macro_rules! bail {
+	($fmt:literal $(, $($arg:tt)*)?) => {
+		return ::core::result::Result::Err($crate::Error::msg(::std::format!($fmt $(, $($arg)*)?)).into())
+	};
+	($error:expr) => {
+		return ::core::result::Result::Err($crate::Error::from($error).into())
+	};
+}
+
+/// Returns early with an [`Error`] if a condition is false. Takes the condition,
+/// then optionally the same arguments as [`bail!`]; without them, the message
+/// names the condition.
+///
+/// ```
+/// # use pinion_rs::{ensure, Error};
+/// fn check(port: u16) -> Result<(), Error> {
+/// 	ensure!(port >= 1024, "port {port} is reserved");
+/// 	ensure!(port != 8080);
+/// 	Ok(())
+/// }
+/// # assert!(check(80).is_err());
+/// # assert_eq!(check(8080).unwrap_err().to_string(), "condition failed: `port != 8080`");
+/// ```
+#[macro_export]
+macro_rules! ensure {
+	($condition:expr $(,)?) => {
+		if !$condition {
+			return ::core::result::Result::Err($crate::Error::msg(
+				::core::concat!("condition failed: `", ::core::stringify!($condition), "`")
+			).into())
+		}
+	};
+	($condition:expr, $($arg:tt)+) => {
+		if !$condition {
+			$crate::bail!($($arg)+)
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::ResultExt;
+
+	#[derive(Debug, PartialEq)]
+	struct Failed;
+
+	impl Display for Failed {
+		fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str("failed") }
+	}
+
+	impl StdError for Failed { }
+
+	/// An error caused by another, to give chains a source.
+	#[derive(Debug)]
+	struct Wrapping(Failed);
+
+	impl Display for Wrapping {
+		fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str("wrapping") }
+	}
+
+	impl StdError for Wrapping {
+		fn source(&self) -> Option<&(dyn StdError + 'static)> { Some(&self.0) }
+	}
+
+	/// Formats the report, minus the backtrace `RUST_BACKTRACE` may have enabled.
+	fn report(error: &Error) -> String {
+		let report = error.report().to_string();
+		let (chain, backtrace) = match report.split_once("\n\nStack backtrace:\n") {
+			Some((chain, _)) => (chain.to_owned(), true),
+			None => (report, false)
+		};
+		assert_eq!(backtrace, error.backtrace().is_some());
+		chain
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(Error::new(Failed).to_string(), "failed");
+		assert_eq!(Error::msg("oops").to_string(), "oops");
+		assert_eq!(Error::msg(format!("oops {}", 1)).to_string(), "oops 1");
+		let error = Error::new(Failed).context("inner").context("outer");
+		assert_eq!(error.to_string(), "outer");
+		assert_eq!(error.contexts().collect::<Vec<_>>(), ["outer", "inner"]);
+		assert_eq!(error.contexts().rev().collect::<Vec<_>>(), ["inner", "outer"]);
+	}
+
+	#[test]
+	fn report_without_context() {
+		assert_eq!(report(&Error::new(Failed)), "failed");
+		assert_eq!(
+			report(&Error::new(Wrapping(Failed))),
+			"wrapping\n\nCaused by:\n    0: failed"
+		);
+	}
+
+	#[test]
+	fn report_with_context() {
+		let error = Error::new(Wrapping(Failed)).context("reading").context("loading");
+		assert_eq!(
+			report(&error),
+			"loading\n\nCaused by:\n    0: reading\n    1: wrapping\n    2: failed"
+		);
+		assert_eq!(format!("{error:?}"), error.report().to_string());
+	}
+
+	#[test]
+	fn fold_contextual() {
+		fn load() -> Result<(), Error> {
+			Err::<(), _>(Error::new(Failed).context("reading")).context("loading")?;
+			Ok(())
+		}
+
+		let error = load().unwrap_err();
+		assert_eq!(error.to_string(), "loading");
+		assert_eq!(error.contexts().collect::<Vec<_>>(), ["loading", "reading"]);
+		// The context is folded into the stack rather than becoming a wrapped error.
+		assert_eq!(error.chain().count(), 1);
+		assert_eq!(error.downcast::<Failed>().ok(), Some(Failed));
+	}
+
+	#[test]
+	fn wrap_contextual_of_other_errors() {
+		let error = Error::from(Contextual::new("loading", Failed));
+		assert_eq!(error.to_string(), "loading");
+		assert_eq!(error.contexts().count(), 0);
+		assert_eq!(report(&error), "loading\n\nCaused by:\n    0: failed");
+		assert_eq!(error.downcast_ref::<Failed>(), Some(&Failed));
+	}
+
+	#[test]
+	fn attachments() {
+		let error = Error::new(Failed).attach(1u32).attach("path").attach(2u32);
+		assert_eq!(error.attachment::<u32>(), Some(&2));
+		assert_eq!(error.attachment::<&str>(), Some(&"path"));
+		assert_eq!(error.attachment::<i64>(), None);
+		assert_eq!(error.to_string(), "failed");
+	}
+
+	#[test]
+	fn downcast() {
+		let error = Error::new(Wrapping(Failed)).context("loading");
+		assert_eq!(error.downcast_ref::<Failed>(), Some(&Failed));
+		assert!(error.downcast_ref::<Wrapping>().is_some());
+		assert!(error.downcast_ref::<fmt::Error>().is_none());
+
+		let error = error.downcast::<Failed>().unwrap_err();
+		assert_eq!(error.to_string(), "loading");
+		assert!(error.downcast::<Wrapping>().is_ok());
+
+		let error = Error::from(fmt::Error);
+		assert_eq!(error.downcast::<fmt::Error>().ok(), Some(fmt::Error));
+	}
+
+	#[test]
+	fn macros() {
+		fn check(port: u16) -> Result<u16, Error> {
+			crate::ensure!(port != 0);
+			crate::ensure!(port >= 1024, "port {port} is reserved");
+			if port == 8080 {
+				crate::bail!(Failed);
+			}
+			Ok(port)
+		}
+
+		assert_eq!(check(0).unwrap_err().to_string(), "condition failed: `port != 0`");
+		assert_eq!(check(80).unwrap_err().to_string(), "port 80 is reserved");
+		assert!(check(8080).unwrap_err().downcast::<Failed>().is_ok());
+		assert_eq!(check(8000).ok(), Some(8000));
+	}
+}
@@ -0,0 +1,335 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accumulation of every error, rather than stopping at the first.
+
+use std::ops::Deref;
+use std::vec;
+
+/// A vector with at least one element, such as the errors accumulated by
+/// [`ResultIterExt::collect_all`] and [`Validated`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NonEmpty<T>(Vec<T>);
+
+impl<T> NonEmpty<T> {
+	/// Creates a vector with a single element.
+	pub fn new(first: T) -> Self {
+		Self(vec![first])
+	}
+
+	/// Optionally wraps `vec` if it isn't empty.
+	pub fn from_vec(vec: Vec<T>) -> Option<Self> {
+		(!vec.is_empty()).then_some(Self(vec))
+	}
+
+	/// Appends an element.
+	pub fn push(&mut self, value: T) {
+		self.0.push(value);
+	}
+
+	/// Returns the first element.
+	pub fn first(&self) -> &T { &self.0[0] }
+
+	/// Returns the last element.
+	pub fn last(&self) -> &T { &self.0[self.0.len() - 1] }
+
+	/// Unwraps the vector.
+	pub fn into_vec(self) -> Vec<T> { self.0 }
+}
+
+impl<T> Deref for NonEmpty<T> {
+	type Target = [T];
+
+	fn deref(&self) -> &[T] { &self.0 }
+}
+
+impl<T> Extend<T> for NonEmpty<T> {
+	fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+		self.0.extend(iter);
+	}
+}
+
+impl<T> IntoIterator for NonEmpty<T> {
+	type Item = T;
+	type IntoIter = vec::IntoIter<T>;
+
+	fn into_iter(self) -> Self::IntoIter { self.0.into_iter() }
+}
+
+impl<'a, T> IntoIterator for &'a NonEmpty<T> {
+	type Item = &'a T;
+	type IntoIter = std::slice::Iter<'a, T>;
+
+	fn into_iter(self) -> Self::IntoIter { self.0.iter() }
+}
+
+impl<T> From<NonEmpty<T>> for Vec<T> {
+	fn from(value: NonEmpty<T>) -> Self { value.0 }
+}
+
+/// Pushes `error` onto `errors`, creating the vector if there is none yet.
+fn accumulate<E>(errors: &mut Option<NonEmpty<E>>, error: E) {
+	match errors {
+		Some(errors) => errors.push(error),
+		None => *errors = Some(NonEmpty::new(error))
+	}
+}
+
+pub trait ResultIterExt<T, E>: Iterator<Item = Result<T, E>> + Sized {
+	/// Collects every [`Ok`] value into `C` if there are no errors, otherwise
+	/// collects every [`Err`] value. Unlike collecting into a [`Result`], doesn't
+	/// stop at the first error.
+	fn collect_all<C: FromIterator<T>>(self) -> Result<C, NonEmpty<E>> {
+		let mut errors = None;
+		let values = self
+			.filter_map(|result| result.map_err(|error| accumulate(&mut errors, error)).ok())
+			.collect();
+		match errors {
+			Some(errors) => Err(errors),
+			None => Ok(values)
+		}
+	}
+	/// Partitions the results into their [`Ok`] and [`Err`] values.
+	fn partition_results<C, D>(self) -> (C, D) where C: Default + Extend<T>, D: Default + Extend<E> {
+		let (mut values, mut errors) = (C::default(), D::default());
+		for result in self {
+			match result {
+				Ok(value) => values.extend(Some(value)),
+				Err(error) => errors.extend(Some(error))
+			}
+		}
+		(values, errors)
+	}
+}
+
+impl<I: Iterator<Item = Result<T, E>>, T, E> ResultIterExt<T, E> for I { }
+
+/// Either a valid value or every error found validating it. Like [`Result`], but
+/// combining validations with [`zip`][] or [`and`][] keeps the errors of both
+/// sides instead of stopping at the first.
+///
+/// [`zip`]: Validated::zip
+/// [`and`]: Validated::and
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Validated<T, E> {
+	/// A valid value.
+	Valid(T),
+	/// The errors found.
+	Invalid(NonEmpty<E>),
+}
+
+use Validated::{Invalid, Valid};
+
+impl<T, E> Validated<T, E> {
+	/// Creates an invalid value from a single `error`.
+	pub fn invalid(error: E) -> Self {
+		Invalid(NonEmpty::new(error))
+	}
+
+	/// Returns `true` if the value is valid.
+	pub fn is_valid(&self) -> bool {
+		matches!(self, Valid(_))
+	}
+
+	/// Combines two validations into a pair of both values if both are valid, or
+	/// every error of either otherwise.
+	pub fn zip<U>(self, other: Validated<U, E>) -> Validated<(T, U), E> {
+		self.zip_with(other, |a, b| (a, b))
+	}
+
+	/// Combines two validations with `f` if both are valid, or collects every
+	/// error of either otherwise.
+	pub fn zip_with<U, R>(self, other: Validated<U, E>, f: impl FnOnce(T, U) -> R) -> Validated<R, E> {
+		match (self, other) {
+			(Valid(a), Valid(b)) => Valid(f(a, b)),
+			(Valid(_), Invalid(errors)) | (Invalid(errors), Valid(_)) => Invalid(errors),
+			(Invalid(mut errors), Invalid(other)) => {
+				errors.extend(other);
+				Invalid(errors)
+			}
+		}
+	}
+
+	/// Returns `other` if both are valid, or every error of either otherwise.
+	pub fn and<U>(self, other: Validated<U, E>) -> Validated<U, E> {
+		self.zip_with(other, |_, b| b)
+	}
+
+	/// Maps a valid value with `f`.
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Validated<U, E> {
+		match self {
+			Valid(value) => Valid(f(value)),
+			Invalid(errors) => Invalid(errors)
+		}
+	}
+
+	/// Maps each error with `f`.
+	pub fn map_err<F>(self, f: impl FnMut(E) -> F) -> Validated<T, F> {
+		match self {
+			Valid(value) => Valid(value),
+			Invalid(errors) => Invalid(NonEmpty(errors.into_iter().map(f).collect()))
+		}
+	}
+
+	/// Converts into a [`Result`] of the value or the errors.
+	pub fn into_result(self) -> Result<T, NonEmpty<E>> {
+		self.into()
+	}
+}
+
+impl<T, E> From<Result<T, E>> for Validated<T, E> {
+	fn from(value: Result<T, E>) -> Self {
+		match value {
+			Ok(value) => Valid(value),
+			Err(error) => Self::invalid(error)
+		}
+	}
+}
+
+impl<T, E> From<Validated<T, E>> for Result<T, NonEmpty<E>> {
+	fn from(value: Validated<T, E>) -> Self {
+		match value {
+			Valid(value) => Ok(value),
+			Invalid(errors) => Err(errors)
+		}
+	}
+}
+
+impl<C: FromIterator<T>, T, E> FromIterator<Validated<T, E>> for Validated<C, E> {
+	/// Collects every valid value if all are valid, or every error otherwise.
+	fn from_iter<I: IntoIterator<Item = Validated<T, E>>>(iter: I) -> Self {
+		let mut errors: Option<NonEmpty<E>> = None;
+		let values = iter
+			.into_iter()
+			.filter_map(|validated| match validated {
+				Valid(value) => Some(value),
+				Invalid(invalid) => {
+					match &mut errors {
+						Some(errors) => errors.extend(invalid),
+						None => errors = Some(invalid)
+					}
+					None
+				}
+			})
+			.collect();
+		match errors {
+			Some(errors) => Invalid(errors),
+			None => Valid(values)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeSet;
+	use super::*;
+
+	fn parse(input: &str) -> Result<u8, String> {
+		input.parse().map_err(|_| format!("invalid: {input}"))
+	}
+
+	#[test]
+	fn non_empty() {
+		let mut errors = NonEmpty::new(1);
+		assert_eq!((errors.first(), errors.last()), (&1, &1));
+		errors.push(2);
+		errors.extend([3, 4]);
+		assert_eq!((errors.first(), errors.last()), (&1, &4));
+		assert_eq!(errors.len(), 4);
+		assert_eq!((&errors).into_iter().sum::<i32>(), 10);
+		assert_eq!(Vec::from(errors.clone()), [1, 2, 3, 4]);
+		assert_eq!(errors.into_vec(), [1, 2, 3, 4]);
+		assert_eq!(NonEmpty::from_vec(vec!['a']).map(NonEmpty::into_vec), Some(vec!['a']));
+		assert_eq!(NonEmpty::<u8>::from_vec(Vec::new()), None);
+	}
+
+	#[test]
+	fn collect_all() {
+		let values: Result<Vec<_>, _> = ["1", "2", "3"].into_iter().map(parse).collect_all();
+		assert_eq!(values, Ok(vec![1, 2, 3]));
+
+		let errors = ["1", "x", "3", "300"].into_iter().map(parse).collect_all::<Vec<_>>();
+		assert_eq!(errors.unwrap_err().into_vec(), ["invalid: x", "invalid: 300"]);
+
+		let set: Result<BTreeSet<_>, NonEmpty<String>> = ["2", "1", "2"].into_iter().map(parse).collect_all();
+		assert_eq!(set, Ok(BTreeSet::from([1, 2])));
+
+		let empty: Result<Vec<u8>, NonEmpty<()>> = std::iter::empty().collect_all();
+		assert_eq!(empty, Ok(Vec::new()));
+	}
+
+	#[test]
+	fn collect_all_consumes_everything() {
+		let mut seen = 0;
+		let errors = ["x", "y", "z"]
+			.into_iter()
+			.inspect(|_| seen += 1)
+			.map(parse)
+			.collect_all::<Vec<_>>();
+		assert_eq!(errors.unwrap_err().len(), 3);
+		assert_eq!(seen, 3);
+	}
+
+	#[test]
+	fn partition_results() {
+		let (values, errors): (Vec<_>, Vec<_>) = ["1", "x", "3", "y"].into_iter().map(parse).partition_results();
+		assert_eq!(values, [1, 3]);
+		assert_eq!(errors, ["invalid: x", "invalid: y"]);
+
+		let (values, errors): (BTreeSet<_>, Vec<String>) = ["5", "5"].into_iter().map(parse).partition_results();
+		assert_eq!(values, BTreeSet::from([5]));
+		assert!(errors.is_empty());
+	}
+
+	#[test]
+	fn zip_and() {
+		let valid = |value: u8| Validated::<u8, &str>::Valid(value);
+		assert_eq!(valid(1).zip(valid(2)), Valid((1, 2)));
+		assert_eq!(valid(1).zip(Validated::<u8, _>::invalid("b")), Validated::invalid("b"));
+		assert_eq!(Validated::<u8, _>::invalid("a").zip(valid(2)), Validated::invalid("a"));
+
+		let both = Validated::<u8, _>::invalid("a").zip(Validated::<u8, _>::invalid("b"));
+		assert_eq!(both.into_result().unwrap_err().into_vec(), ["a", "b"]);
+
+		assert_eq!(valid(1).and(valid(2)), Valid(2));
+		assert_eq!(valid(1).zip_with(valid(2), |a, b| a + b), Valid(3));
+		let three = Validated::<u8, _>::invalid("a")
+			.and(Validated::<u8, _>::invalid("b"))
+			.and(Validated::<u8, _>::invalid("c"));
+		assert_eq!(three.into_result().unwrap_err().into_vec(), ["a", "b", "c"]);
+	}
+
+	#[test]
+	fn map_and_map_err() {
+		assert_eq!(Validated::<_, &str>::Valid(2).map(|value| value * 2), Valid(4));
+		assert_eq!(Validated::<u8, _>::invalid("a").map(|value| value * 2), Validated::invalid("a"));
+		assert_eq!(Validated::<u8, _>::Valid(2).map_err(str::len), Valid(2));
+
+		let errors = Validated::<u8, _>::invalid("a").and(Validated::<u8, _>::invalid("bcd"));
+		assert_eq!(errors.map_err(str::len).into_result().unwrap_err().into_vec(), [1, 3]);
+	}
+
+	#[test]
+	fn conversions() {
+		assert_eq!(Validated::from(parse("1")), Valid(1));
+		assert_eq!(Validated::from(parse("x")), Validated::invalid("invalid: x".to_owned()));
+		assert!(Validated::from(parse("1")).is_valid());
+		assert!(!Validated::from(parse("x")).is_valid());
+		assert_eq!(Result::from(Validated::<_, ()>::Valid(1)), Ok(1));
+		assert_eq!(Validated::<u8, _>::invalid(()).into_result(), Err(NonEmpty::new(())));
+	}
+
+	#[test]
+	fn from_iter() {
+		let valid: Validated<Vec<_>, &str> = [Valid(1), Valid(2)].into_iter().collect();
+		assert_eq!(valid, Valid(vec![1, 2]));
+
+		let errors = Validated::<u8, _>::invalid("b").and(Validated::invalid("c"));
+		let invalid: Validated<Vec<_>, _> = [Valid(1), Validated::invalid("a"), errors, Valid(4)]
+			.into_iter()
+			.collect();
+		assert_eq!(invalid.into_result().unwrap_err().into_vec(), ["a", "b", "c"]);
+
+		let empty: Validated<Vec<u8>, ()> = std::iter::empty().collect();
+		assert_eq!(empty, Valid(Vec::new()));
+	}
+}
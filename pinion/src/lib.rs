@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
+#![allow(clippy::tabs_in_doc_comments)]
+
 mod fallible;
 mod num;
 mod ptr;
@@ -1,26 +1,111 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use std::num::NonZero;
 use std::ops::RangeBounds;
 
+mod arith;
+mod bits;
+mod digits;
+mod human;
+mod int;
+mod parse;
+#[cfg(feature = "primes")]
+mod primes;
+mod refine;
+mod stats;
+
+pub use bits::*;
+pub use digits::Digits;
+pub use human::*;
+pub use int::*;
+pub use parse::{ParseHumanError, ParseHumanErrorKind};
+#[cfg(feature = "primes")]
+pub use primes::PrimesIn;
+pub use refine::*;
+pub use stats::{Sample, StatsExt};
+
 mod sealed {
-	pub trait SealedNumExt { }
-	impl SealedNumExt for i8 { }
-	impl SealedNumExt for u8 { }
-	impl SealedNumExt for i16 { }
-	impl SealedNumExt for u16 { }
-	impl SealedNumExt for i32 { }
-	impl SealedNumExt for u32 { }
-	impl SealedNumExt for i64 { }
-	impl SealedNumExt for u64 { }
-	//impl SealedNumExt for i128 { }
-	//impl SealedNumExt for u128 { }
-	impl SealedNumExt for isize { }
-	impl SealedNumExt for usize { }
+	use std::cmp::Ordering;
+	use std::num::NonZero;
+
+	/// Conversions backing [`NumExt`]'s casts. The trait can't be named outside
+	/// this crate, but its methods are still callable through a `NumExt` bound,
+	/// so they're hidden from the docs instead. They aren't part of the supported
+	/// API and may change in any release.
+	///
+	/// [`NumExt`]: super::NumExt
+	pub trait SealedNumExt: Copy {
+		/// Returns whether the value is negative, and its bits sign-extended to
+		/// 128 bits. Together these identify the value across all integer types.
+		#[doc(hidden)]
+		fn to_bits(self) -> (bool, u128);
+		/// Truncates 128 bits to this type's width.
+		#[doc(hidden)]
+		fn from_bits(bits: u128) -> Self;
+		/// Returns the minimum and maximum values of this type.
+		#[doc(hidden)]
+		fn bounds() -> (Self, Self);
+	}
+
+	/// Accumulation backing [`StatsExt`]. Like [`SealedNumExt`], its items are
+	/// reachable through a [`Sample`] bound, so they're hidden from the docs and
+	/// aren't part of the supported API.
+	///
+	/// [`StatsExt`]: super::StatsExt
+	/// [`Sample`]: super::Sample
+	pub trait SealedSample: Copy + PartialOrd {
+		/// A running sum wide enough that adding values can't overflow.
+		#[doc(hidden)]
+		type Sum: Copy + Default;
+		/// Adds this value to a running sum.
+		#[doc(hidden)]
+		fn accumulate(self, sum: Self::Sum) -> Self::Sum;
+		/// Converts a sum back to this type, if it fits.
+		#[doc(hidden)]
+		fn narrow(sum: Self::Sum) -> Option<Self>;
+		/// Approximates a sum as a float.
+		#[doc(hidden)]
+		fn sum_to_f64(sum: Self::Sum) -> f64;
+		/// Approximates this value as a float.
+		#[doc(hidden)]
+		fn to_f64(self) -> f64;
+		/// Compares by a total order, the one [`f64::total_cmp`] uses for floats.
+		#[doc(hidden)]
+		fn cmp_total(&self, other: &Self) -> Ordering;
+	}
+
+	pub trait SealedNonZeroExt { }
+	impl SealedNonZeroExt for NonZero<i8> { }
+	impl SealedNonZeroExt for NonZero<u8> { }
+	impl SealedNonZeroExt for NonZero<i16> { }
+	impl SealedNonZeroExt for NonZero<u16> { }
+	impl SealedNonZeroExt for NonZero<i32> { }
+	impl SealedNonZeroExt for NonZero<u32> { }
+	impl SealedNonZeroExt for NonZero<i64> { }
+	impl SealedNonZeroExt for NonZero<u64> { }
+	impl SealedNonZeroExt for NonZero<i128> { }
+	impl SealedNonZeroExt for NonZero<u128> { }
+	impl SealedNonZeroExt for NonZero<isize> { }
+	impl SealedNonZeroExt for NonZero<usize> { }
+
+	pub trait SealedFloatExt { }
+	impl SealedFloatExt for f32 { }
+	impl SealedFloatExt for f64 { }
 }
 
 pub trait NumExt: PartialOrd<Self> + Sized + sealed::SealedNumExt {
+	/// The [`NonZero`] counterpart of this integer type.
+	type NonZero: NonZeroExt<Int = Self>;
+	/// The unsigned integer type of the same width, able to hold any magnitude.
+	type Unsigned: Unsigned;
+
 	/// Optionally returns this number if it is not zero.
 	fn non_zero(self) -> Option<Self>;
+	/// Optionally returns this number as its [`NonZero`] counterpart if it is not
+	/// zero. Same as [`non_zero`][], but keeps the proof in the type.
+	///
+	/// [`non_zero`]: NumExt::non_zero
+	fn to_non_zero(self) -> Option<Self::NonZero>;
 	/// Optionally returns this number if it is positive. Has the same effect as
 	/// [`non_zero`][] for unsigned integers.
 	///
@@ -38,6 +123,12 @@ pub trait NumExt: PartialOrd<Self> + Sized + sealed::SealedNumExt {
 	fn in_range<R: RangeBounds<Self>>(self, range: R) -> Option<Self> {
 		range.contains(&self).then_some(self)
 	}
+	/// Clamps this number into `range`'s bounds, or returns `None` if the range is
+	/// empty.
+	fn clamp_to<R: RangeBounds<Self>>(self, range: R) -> Option<Self>;
+	/// Wraps this number into `range`'s bounds modulo the range's width, as with a
+	/// ring buffer index, or returns `None` if the range is empty.
+	fn wrap_into<R: RangeBounds<Self>>(self, range: R) -> Option<Self>;
 	/// Optionally returns this number if it is even.
 	fn even(self) -> Option<Self> { self.is_even().then_some(self) }
 	/// Optionally returns this number if it is odd.
@@ -47,6 +138,207 @@ pub trait NumExt: PartialOrd<Self> + Sized + sealed::SealedNumExt {
 	/// Returns `true` if this number is odd.
 	fn is_odd(&self) -> bool { !self.is_even() }
 
+	/// Returns the greatest common divisor of this number and `other`, computed on
+	/// their magnitudes. The result is unsigned since the magnitude of a signed
+	/// `MIN` doesn't fit its own type. The divisor of zero and zero is zero.
+	fn gcd(self, other: Self) -> Self::Unsigned;
+	/// Returns the least common multiple of this number and `other`, computed on
+	/// their magnitudes, or `None` if it overflows.
+	fn lcm(self, other: Self) -> Option<Self::Unsigned>;
+	/// Returns the greatest common divisor `g` of this number and `other`, with
+	/// Bézout coefficients `x` and `y` such that `self * x + other * y = g`. The
+	/// coefficients are always representable as `i128`, for any integer type.
+	fn extended_gcd(self, other: Self) -> (Self::Unsigned, i128, i128);
+	/// Computes this number raised to `exp`, modulo `modulus`. The result is the
+	/// least non-negative residue, even for negative numbers. Returns `None` if
+	/// `modulus` isn't positive.
+	fn pow_mod(self, exp: Self::Unsigned, modulus: Self) -> Option<Self>;
+	/// Computes the multiplicative inverse of this number modulo `modulus`, as the
+	/// least non-negative residue. Returns `None` if `modulus` isn't positive or
+	/// the inverse doesn't exist, i.e. the two aren't coprime.
+	fn inv_mod(self, modulus: Self) -> Option<Self>;
+	/// Returns `true` if this number is an integer power of `base`, including the
+	/// zeroth power. Negative bases produce negative odd powers.
+	fn is_power_of(&self, base: Self) -> bool;
+
+	/// Returns the number of digits in this number's magnitude in `radix`. Zero
+	/// has one digit.
+	///
+	/// # Panics
+	///
+	/// Panics if `radix` is less than 2.
+	fn digit_count(&self, radix: u32) -> u32;
+	/// Returns an iterator over the digits of this number's magnitude in `radix`,
+	/// most significant first. Call [`rev`][] on it for least significant first.
+	///
+	/// # Panics
+	///
+	/// Panics if `radix` is less than 2.
+	///
+	/// [`rev`]: Iterator::rev
+	fn digits(self, radix: u32) -> Digits;
+	/// Returns the sum of the digits of this number's magnitude in `radix`.
+	///
+	/// # Panics
+	///
+	/// Panics if `radix` is less than 2.
+	fn digit_sum(&self, radix: u32) -> Self::Unsigned;
+	/// Reverses the digits of this number in `radix`, keeping its sign. Trailing
+	/// zeros are dropped. Returns `None` if the result overflows.
+	///
+	/// # Panics
+	///
+	/// Panics if `radix` is less than 2.
+	fn reverse_digits(self, radix: u32) -> Option<Self>;
+	/// Builds a number from `digits` in `radix`, most significant first. Returns
+	/// `None` if any digit is not less than `radix`, or if the result overflows.
+	///
+	/// # Panics
+	///
+	/// Panics if `radix` is less than 2.
+	fn from_digits<I: IntoIterator<Item = u32>>(digits: I, radix: u32) -> Option<Self>;
+
+	/// Rounds this number up to the nearest multiple of `multiple`.
+	///
+	/// # Panics
+	///
+	/// Panics if `multiple` is zero or the result overflows.
+	fn round_up_to(self, multiple: Self) -> Self {
+		self.checked_round_up_to(multiple).expect("multiple is zero or rounding overflowed")
+	}
+	/// Rounds this number up to the nearest multiple of `multiple`, returning
+	/// `None` if `multiple` is zero or the result overflows.
+	fn checked_round_up_to(self, multiple: Self) -> Option<Self>;
+	/// Rounds this number down to the nearest multiple of `multiple`.
+	///
+	/// # Panics
+	///
+	/// Panics if `multiple` is zero or the result overflows.
+	fn round_down_to(self, multiple: Self) -> Self {
+		self.checked_round_down_to(multiple).expect("multiple is zero or rounding overflowed")
+	}
+	/// Rounds this number down to the nearest multiple of `multiple`, returning
+	/// `None` if `multiple` is zero or the result overflows.
+	fn checked_round_down_to(self, multiple: Self) -> Option<Self>;
+	/// Divides this number by `rhs`, rounding the quotient towards positive
+	/// infinity. Named apart from the inherent `div_ceil`, which only exists on
+	/// unsigned integers.
+	///
+	/// # Panics
+	///
+	/// Panics if `rhs` is zero or the division overflows.
+	fn div_round_up(self, rhs: Self) -> Self {
+		self.checked_div_round_up(rhs).expect("divisor is zero or division overflowed")
+	}
+	/// Divides this number by `rhs`, rounding the quotient towards positive
+	/// infinity. Returns `None` if `rhs` is zero or the division overflows.
+	fn checked_div_round_up(self, rhs: Self) -> Option<Self>;
+	/// Divides this number by `rhs`, rounding the quotient towards negative
+	/// infinity. Unlike `/`, which truncates towards zero, this is correct for
+	/// signed operands of different signs. Named apart from the inherent
+	/// `div_floor`, which is unstable.
+	///
+	/// # Panics
+	///
+	/// Panics if `rhs` is zero or the division overflows.
+	fn div_round_down(self, rhs: Self) -> Self {
+		self.checked_div_round_down(rhs).expect("divisor is zero or division overflowed")
+	}
+	/// Divides this number by `rhs`, rounding the quotient towards negative
+	/// infinity. Returns `None` if `rhs` is zero or the division overflows.
+	fn checked_div_round_down(self, rhs: Self) -> Option<Self>;
+	/// Optionally returns this number if it is divisible by `divisor`.
+	fn divisible_by(self, divisor: Self) -> Option<Self> {
+		self.is_divisible_by(divisor).then_some(self)
+	}
+	/// Returns `true` if this number is divisible by `divisor`, i.e. a multiple of
+	/// it. Only zero is divisible by zero.
+	fn is_divisible_by(&self, divisor: Self) -> bool;
+	/// Rounds this number up to the nearest multiple of `align`, which must be a
+	/// positive power of two.
+	///
+	/// # Panics
+	///
+	/// Panics if `align` is not a positive power of two, or the result overflows.
+	fn align_up_pow2(self, align: Self) -> Self {
+		self.checked_align_up_pow2(align).expect("alignment is not a power of two or rounding overflowed")
+	}
+	/// Rounds this number up to the nearest multiple of `align`, returning `None`
+	/// if `align` is not a positive power of two or the result overflows.
+	fn checked_align_up_pow2(self, align: Self) -> Option<Self>;
+
+	/// Casts this number to integer type `U`, returning `None` if it's out of `U`'s
+	/// range.
+	fn cast_checked<U: NumExt>(self) -> Option<U> {
+		let bits = self.to_bits();
+		let cast = U::from_bits(bits.1);
+		(cast.to_bits() == bits).then_some(cast)
+	}
+	/// Casts this number to integer type `U`, saturating at `U`'s bounds if it's
+	/// out of range.
+	fn cast_saturating<U: NumExt>(self) -> U {
+		self.cast_checked().unwrap_or_else(|| {
+			let (min, max) = U::bounds();
+			if self.to_bits().0 { min } else { max }
+		})
+	}
+	/// Casts this number to integer type `U`, wrapping around modulo `U`'s width
+	/// if it's out of range. Same as an `as` cast.
+	fn cast_wrapping<U: NumExt>(self) -> U {
+		U::from_bits(self.to_bits().1)
+	}
+	/// Casts this number to integer type `U`, which can hold every value of this
+	/// type on any platform. Unlike the other casts, this can't fail, which is
+	/// checked at compile time.
+	fn widen<U: NumExt + From<Self>>(self) -> U {
+		U::from(self)
+	}
+
+	/// Parses a human-written number, accepting:
+	///
+	/// - `_` digit separators between digits, such as `1_000_000`
+	/// - `0x`, `0o` and `0b` prefixes, such as `0x1F`
+	/// - fractions and exponents resulting in an integer, such as `1.5k` or `1e6`
+	/// - SI and IEC unit suffixes, optionally followed by `B`, such as `10k` or
+	///   `1.5GiB`
+	///
+	/// Units are case-insensitive, and may be separated from the number by spaces.
+	/// A unit can't follow an exponent, and exa is written `EB` or `Ei` since a
+	/// lone `E` would read as an exponent. Overflow is reported at the digit where
+	/// the number stops fitting, or else at its exponent or unit.
+	fn parse_human(input: &str) -> Result<Self, ParseHumanError> {
+		let signed_bits = |negative: bool, magnitude: u128| {
+			if negative { magnitude.wrapping_neg() } else { magnitude }
+		};
+		let fits = |negative, magnitude| {
+			let bits = signed_bits(negative, magnitude);
+			Self::from_bits(bits).to_bits() == (negative && magnitude != 0, bits)
+		};
+		let (negative, magnitude) = parse::parse(input, fits)?;
+		Ok(Self::from_bits(signed_bits(negative, magnitude)))
+	}
+
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number as a
+	/// byte count in SI units, powers of 1000 such as `1.5 MB`.
+	fn as_bytes_si(self) -> ByteSize { ByteSize::new(self.to_bits(), false) }
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number as a
+	/// byte count in IEC units, powers of 1024 such as `1.5 MiB`.
+	fn as_bytes_iec(self) -> ByteSize { ByteSize::new(self.to_bits(), true) }
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number with
+	/// its digits grouped in threes by `separator`, such as `1,234,567`.
+	fn with_separators(self, separator: char) -> Separated {
+		Separated::new(self.to_bits(), separator)
+	}
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number as an
+	/// English ordinal, such as `21st`.
+	fn ordinal(self) -> Ordinal { Ordinal::new(self.to_bits()) }
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number as a
+	/// Roman numeral, such as `XLII`, or `None` if it is outside `1..=3999`.
+	fn roman(self) -> Option<Roman> { Roman::new(self.to_bits()) }
+	/// Returns a [`Display`](std::fmt::Display) adapter showing this number in
+	/// English words, such as `one hundred twenty-three`.
+	fn to_words(self) -> Words { Words::new(self.to_bits()) }
+
 	#[cfg(feature = "primes")]
 	/// Optionally returns this number if it is a prime, using the [`primal`] crate.
 	fn prime(self) -> Option<Self> {
@@ -54,7 +346,73 @@ pub trait NumExt: PartialOrd<Self> + Sized + sealed::SealedNumExt {
 	}
 
 	#[cfg(feature = "primes")]
-	/// Returns `true` if this number is a prime, using the [`primal`] crate.
+	/// Returns `true` if this number is a prime, using the [`primal`] crate. Values
+	/// beyond the 64-bit range, only reachable by `i128` and `u128`, are checked
+	/// with a Miller-Rabin test that is deterministic below about 2⁸¹, and with a
+	/// Baillie-PSW test above that. Baillie-PSW has no known counterexample, but it
+	/// isn't proven correct for every 128-bit value.
+	fn is_prime(&self) -> bool;
+
+	#[cfg(feature = "primes")]
+	/// Returns the smallest prime greater than this number, or `None` if it would
+	/// overflow.
+	fn next_prime(self) -> Option<Self>;
+
+	#[cfg(feature = "primes")]
+	/// Returns the largest prime less than this number, or `None` if there is none.
+	fn prev_prime(self) -> Option<Self>;
+
+	#[cfg(feature = "primes")]
+	/// Returns the prime factors of this number's magnitude in ascending order,
+	/// paired with their multiplicities. Zero and one have no prime factors.
+	///
+	/// Factors are found with Pollard's rho, in about `p^(1/2)` steps for the
+	/// second largest prime factor `p`, falling back to Lenstra's elliptic curve
+	/// method once `p` exceeds about 36 bits. The elliptic curve method's cost grows
+	/// subexponentially with `p`, up to about a second in a release build for the
+	/// worst case, a `u128` made of two 64-bit primes.
+	fn prime_factors(self) -> Vec<(Self, u32)>;
+
+	#[cfg(feature = "primes")]
+	/// Returns `true` if this number and `other` share no factor other than one.
+	fn is_coprime(&self, other: Self) -> bool;
+
+	#[cfg(feature = "primes")]
+	/// Returns Euler's totient of this number, the count of integers up to it which
+	/// are coprime to it, or `None` if it isn't positive. This factors the number,
+	/// at the cost described on [`prime_factors`][].
+	///
+	/// [`prime_factors`]: NumExt::prime_factors
+	fn totient(self) -> Option<Self>;
+
+	#[cfg(feature = "primes")]
+	/// Returns an iterator over the primes within `range`.
+	fn primes_in<R: RangeBounds<Self>>(range: R) -> PrimesIn<Self>;
+}
+
+pub trait NonZeroExt: PartialOrd<Self> + Copy + sealed::SealedNonZeroExt {
+	/// The underlying integer type.
+	type Int: NumExt<NonZero = Self>;
+
+	/// Optionally returns this number if it is within `range`'s bounds.
+	fn in_range<R: RangeBounds<Self::Int>>(self, range: R) -> Option<Self>;
+	/// Optionally returns this number if it is even.
+	fn even(self) -> Option<Self> { self.is_even().then_some(self) }
+	/// Optionally returns this number if it is odd.
+	fn odd(self) -> Option<Self> { self.is_odd().then_some(self) }
+	/// Returns `true` if this number is even.
+	fn is_even(&self) -> bool;
+	/// Returns `true` if this number is odd.
+	fn is_odd(&self) -> bool { !self.is_even() }
+
+	#[cfg(feature = "primes")]
+	/// Optionally returns this number if it is a prime. See [`NumExt::is_prime`].
+	fn prime(self) -> Option<Self> {
+		self.is_prime().then_some(self)
+	}
+
+	#[cfg(feature = "primes")]
+	/// Returns `true` if this number is a prime. See [`NumExt::is_prime`].
 	fn is_prime(&self) -> bool;
 }
 
@@ -63,14 +421,53 @@ pub trait SNumExt: NumExt {
 	fn negative(self) -> Option<Self>;
 }
 
+pub trait FloatExt: PartialOrd<Self> + Sized + sealed::SealedFloatExt {
+	/// Optionally returns this number if it is not zero. Both positive and negative
+	/// zero are rejected.
+	fn non_zero(self) -> Option<Self>;
+	/// Optionally returns this number if it is greater than zero.
+	fn positive(self) -> Option<Self>;
+	/// Optionally returns this number if it is less than zero.
+	fn negative(self) -> Option<Self>;
+	/// Optionally returns this number if it is greater than `other`.
+	fn greater_than<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B>;
+	/// Optionally returns this number if it is less than `other`.
+	fn less_than<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B>;
+	/// Optionally returns this number if it is greater than or equal to `other`.
+	fn greater_than_or_equal<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B>;
+	/// Optionally returns this number if it is less than or equal to `other`.
+	fn less_than_or_equal<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B>;
+	/// Optionally returns this number if it is within `range`'s bounds. `NaN` is
+	/// never within any range, even the unbounded `..`.
+	fn in_range<R: RangeBounds<Self>>(self, range: R) -> Option<Self> {
+		self.non_nan().filter(|n| range.contains(n))
+	}
+	/// Optionally returns this number if it is neither infinite nor `NaN`.
+	fn finite(self) -> Option<Self>;
+	/// Optionally returns this number if it is not `NaN`.
+	fn non_nan(self) -> Option<Self>;
+	/// Optionally returns this number if it is neither zero, infinite, subnormal,
+	/// nor `NaN`.
+	fn normal(self) -> Option<Self>;
+	/// Optionally returns this number if it is finite and has no fractional part.
+	fn integral(self) -> Option<Self>;
+}
+
 macro_rules! nums {
-    ($($ty:ident)+) => {
+    ($($ty:ident: $unsigned:ident)+) => {
 		$(
 		impl NumExt for $ty {
+			type NonZero = NonZero<$ty>;
+			type Unsigned = $unsigned;
+
 			fn non_zero(self) -> Option<Self> {
 				(self != 0).then_some(self)
 			}
 
+			fn to_non_zero(self) -> Option<Self::NonZero> {
+				NonZero::new(self)
+			}
+
 			fn positive(self) -> Option<Self> {
 				(self > 0).then_some(self)
 			}
@@ -91,13 +488,219 @@ macro_rules! nums {
 				(self <= other).then_some(self)
 			}
 
+			fn clamp_to<R: RangeBounds<Self>>(self, range: R) -> Option<Self> {
+				let (lower, upper) = arith::key_bounds(&range, <$ty>::MIN, <$ty>::MAX)?;
+				let key = arith::to_key(self, <$ty>::MIN).clamp(lower, upper);
+				arith::from_key(key, <$ty>::MIN)
+			}
+
+			fn wrap_into<R: RangeBounds<Self>>(self, range: R) -> Option<Self> {
+				let (lower, upper) = arith::key_bounds(&range, <$ty>::MIN, <$ty>::MAX)?;
+				let key = arith::wrap_key(arith::to_key(self, <$ty>::MIN), lower, upper);
+				arith::from_key(key, <$ty>::MIN)
+			}
+
 			fn is_even(&self) -> bool { self % 2 == 0 }
 
+			fn gcd(self, other: Self) -> $unsigned {
+				arith::gcd(arith::magnitude(self), arith::magnitude(other)) as $unsigned
+			}
+
+			fn lcm(self, other: Self) -> Option<$unsigned> {
+				let (a, b) = (arith::magnitude(self), arith::magnitude(other));
+				if a == 0 || b == 0 {
+					return Some(0)
+				}
+
+				(a / arith::gcd(a, b)).checked_mul(b)?.try_into().ok()
+			}
+
+			fn extended_gcd(self, other: Self) -> ($unsigned, i128, i128) {
+				let (gcd, x, y) = arith::extended_gcd(arith::magnitude(self), arith::magnitude(other));
+				let x = if arith::is_negative(self) { -x } else { x };
+				let y = if arith::is_negative(other) { -y } else { y };
+				(gcd as $unsigned, x, y)
+			}
+
+			fn pow_mod(self, exp: $unsigned, modulus: Self) -> Option<Self> {
+				let modulus = u128::try_from(modulus).ok().filter(|&m| m > 0)?;
+				let base = arith::residue(self, modulus);
+				Some(arith::pow_mod(base, exp as u128, modulus) as Self)
+			}
+
+			fn inv_mod(self, modulus: Self) -> Option<Self> {
+				let modulus = u128::try_from(modulus).ok().filter(|&m| m > 0)?;
+				let inverse = arith::inv_mod(arith::residue(self, modulus), modulus)?;
+				Some(inverse as Self)
+			}
+
+			fn is_power_of(&self, base: Self) -> bool {
+				arith::is_power_of(
+					arith::magnitude(*self),
+					arith::is_negative(*self),
+					arith::magnitude(base),
+					arith::is_negative(base)
+				)
+			}
+
+			fn digit_count(&self, radix: u32) -> u32 {
+				digits::digit_count(arith::magnitude(*self), radix)
+			}
+
+			fn digits(self, radix: u32) -> Digits {
+				Digits::new(arith::magnitude(self), radix)
+			}
+
+			fn digit_sum(&self, radix: u32) -> $unsigned {
+				// The sum of digits never exceeds the magnitude.
+				digits::digit_sum(arith::magnitude(*self), radix) as $unsigned
+			}
+
+			fn reverse_digits(self, radix: u32) -> Option<Self> {
+				let reversed = digits::reverse_digits(arith::magnitude(self), radix)?;
+				arith::from_magnitude(reversed, arith::is_negative(self))
+			}
+
+			fn from_digits<I: IntoIterator<Item = u32>>(digits: I, radix: u32) -> Option<Self> {
+				digits::from_digits(digits, radix)?.try_into().ok()
+			}
+
+			fn checked_round_up_to(self, multiple: Self) -> Option<Self> {
+				if multiple == 0 {
+					return None
+				}
+
+				// Only MIN.rem_euclid(-1) overflows, which is zero.
+				let remainder = self.checked_rem_euclid(multiple).unwrap_or(0);
+				if remainder == 0 {
+					return Some(self)
+				}
+
+				// Less than the multiple's magnitude, so this fits.
+				let distance = (arith::magnitude(multiple) - remainder as u128) as Self;
+				self.checked_add(distance)
+			}
+
+			fn checked_round_down_to(self, multiple: Self) -> Option<Self> {
+				if multiple == 0 {
+					return None
+				}
+
+				// Only MIN.rem_euclid(-1) overflows, which is zero.
+				self.checked_sub(self.checked_rem_euclid(multiple).unwrap_or(0))
+			}
+
+			fn checked_div_round_up(self, rhs: Self) -> Option<Self> {
+				let quotient = self.checked_div(rhs)?;
+				let remainder = self % rhs;
+				if remainder != 0 && arith::is_negative(remainder) == arith::is_negative(rhs) {
+					Some(quotient + 1)
+				} else {
+					Some(quotient)
+				}
+			}
+
+			fn checked_div_round_down(self, rhs: Self) -> Option<Self> {
+				let quotient = self.checked_div(rhs)?;
+				let remainder = self % rhs;
+				if remainder != 0 && arith::is_negative(remainder) != arith::is_negative(rhs) {
+					Some(quotient - 1)
+				} else {
+					Some(quotient)
+				}
+			}
+
+			fn is_divisible_by(&self, divisor: Self) -> bool {
+				if divisor == 0 {
+					*self == 0
+				} else {
+					// Only MIN % -1 overflows, which is divisible.
+					self.checked_rem(divisor).unwrap_or(0) == 0
+				}
+			}
+
+			fn checked_align_up_pow2(self, align: Self) -> Option<Self> {
+				let mask = align.positive().filter(|align| align & (align - 1) == 0)? - 1;
+				Some(self.checked_add(mask)? & !mask)
+			}
+
 			#[cfg(feature = "primes")]
 			fn is_prime(&self) -> bool {
-				*self > 1 && primal::is_prime(*self as u64)
+				*self > 1 && primes::is_prime(*self as u128)
+			}
+
+			#[cfg(feature = "primes")]
+			fn next_prime(self) -> Option<Self> {
+				if self < 2 {
+					return Some(2)
+				}
+
+				primes::next_prime(self as u128)?.try_into().ok()
+			}
+
+			#[cfg(feature = "primes")]
+			fn prev_prime(self) -> Option<Self> {
+				if self <= 2 {
+					return None
+				}
+
+				primes::prev_prime(self as u128).map(|p| p as Self)
+			}
+
+			#[cfg(feature = "primes")]
+			fn prime_factors(self) -> Vec<(Self, u32)> {
+				primes::prime_factors(arith::magnitude(self))
+					.into_iter()
+					.map(|(p, multiplicity)| (p as Self, multiplicity))
+					.collect()
+			}
+
+			#[cfg(feature = "primes")]
+			fn is_coprime(&self, other: Self) -> bool {
+				self.gcd(other) == 1
+			}
+
+			#[cfg(feature = "primes")]
+			fn totient(self) -> Option<Self> {
+				self.positive().map(|n| primes::totient(n as u128) as Self)
+			}
+
+			#[cfg(feature = "primes")]
+			fn primes_in<R: RangeBounds<Self>>(range: R) -> PrimesIn<Self> {
+				PrimesIn::new(range)
 			}
 		}
+
+		impl NonZeroExt for NonZero<$ty> {
+			type Int = $ty;
+
+			fn in_range<R: RangeBounds<$ty>>(self, range: R) -> Option<Self> {
+				range.contains(&self.get()).then_some(self)
+			}
+
+			fn is_even(&self) -> bool { self.get().is_even() }
+
+			#[cfg(feature = "primes")]
+			fn is_prime(&self) -> bool { self.get().is_prime() }
+		}
+
+		impl Int for $ty {
+			const ZERO: Self = 0;
+			const ONE: Self = 1;
+			const MIN: Self = <$ty>::MIN;
+			const MAX: Self = <$ty>::MAX;
+			const BITS: u32 = <$ty>::BITS;
+		}
+
+		impl sealed::SealedNumExt for $ty {
+			fn to_bits(self) -> (bool, u128) {
+				(arith::is_negative(self), self as i128 as u128)
+			}
+
+			fn from_bits(bits: u128) -> Self { bits as Self }
+
+			fn bounds() -> (Self, Self) { (<$ty>::MIN, <$ty>::MAX) }
+		}
 		)+
 	};
 }
@@ -110,9 +713,287 @@ macro_rules! snums {
 				(self < 0).then_some(self)
 			}
 		}
+
+		impl Signed for $ty { }
+		)+
+	};
+}
+
+macro_rules! unums {
+    ($($ty:ident)+) => {
+		$(
+		impl Unsigned for $ty { }
+		)+
+	};
+}
+
+nums! {
+	i8: u8 u8: u8 i16: u16 u16: u16 i32: u32 u32: u32
+	i64: u64 u64: u64 i128: u128 u128: u128 isize: usize usize: usize
+}
+snums! { i8 i16 i32 i64 i128 isize }
+unums! { u8 u16 u32 u64 u128 usize }
+
+macro_rules! floats {
+    ($($ty:ident)+) => {
+		$(
+		impl FloatExt for $ty {
+			fn non_zero(self) -> Option<Self> {
+				(self != 0.0).then_some(self)
+			}
+
+			fn positive(self) -> Option<Self> {
+				(self > 0.0).then_some(self)
+			}
+
+			fn negative(self) -> Option<Self> {
+				(self < 0.0).then_some(self)
+			}
+
+			fn greater_than<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B> {
+				(self > other).then_some(self)
+			}
+
+			fn less_than<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B> {
+				(self < other).then_some(self)
+			}
+
+			fn greater_than_or_equal<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B> {
+				(self >= other).then_some(self)
+			}
+
+			fn less_than_or_equal<B>(self, other: B) -> Option<Self> where Self: PartialOrd<B> {
+				(self <= other).then_some(self)
+			}
+
+			fn finite(self) -> Option<Self> {
+				self.is_finite().then_some(self)
+			}
+
+			fn non_nan(self) -> Option<Self> {
+				(!self.is_nan()).then_some(self)
+			}
+
+			fn normal(self) -> Option<Self> {
+				self.is_normal().then_some(self)
+			}
+
+			fn integral(self) -> Option<Self> {
+				(self.is_finite() && self.fract() == 0.0).then_some(self)
+			}
+		}
 		)+
 	};
 }
 
-nums! { i8 u8 i16 u16 i32 u32 i64 u64 isize usize }
-snums! { i8 i16 i32 i64 isize }
+floats! { f32 f64 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn float_filters() {
+		assert_eq!(0.0f64.non_zero(), None);
+		assert_eq!((-0.0f64).non_zero(), None);
+		assert_eq!((-0.0f32).positive(), None);
+		assert_eq!((-0.0f32).negative(), None);
+		assert_eq!(1.5f64.non_zero(), Some(1.5));
+		assert_eq!((-1.5f32).negative(), Some(-1.5));
+		assert_eq!(2.0f64.greater_than(1.0), Some(2.0));
+		assert_eq!(2.0f64.less_than_or_equal(2.0), Some(2.0));
+	}
+
+	#[test]
+	fn float_nan() {
+		assert_eq!(f64::NAN.in_range(..), None);
+		assert_eq!(f64::NAN.in_range(f64::NEG_INFINITY..=f64::INFINITY), None);
+		assert_eq!(f32::NAN.non_nan(), None);
+		assert_eq!(f32::NAN.finite(), None);
+		assert_eq!(f64::NAN.normal(), None);
+		assert_eq!(f64::NAN.integral(), None);
+		assert_eq!(f64::NAN.non_zero().map(f64::is_nan), Some(true));
+		assert_eq!(f64::INFINITY.non_nan(), Some(f64::INFINITY));
+		assert_eq!(1.0f64.in_range(0.0..1.0), None);
+		assert_eq!(0.5f64.in_range(0.0..1.0), Some(0.5));
+	}
+
+	#[test]
+	fn float_classes() {
+		let subnormal = f64::MIN_POSITIVE / 2.0;
+		assert_eq!(subnormal.normal(), None);
+		assert_eq!(subnormal.finite(), Some(subnormal));
+		assert_eq!((f32::MIN_POSITIVE / 4.0).normal(), None);
+		assert_eq!(f64::MIN_POSITIVE.normal(), Some(f64::MIN_POSITIVE));
+		assert_eq!(0.0f64.normal(), None);
+		assert_eq!(f64::INFINITY.normal(), None);
+		assert_eq!(f64::INFINITY.finite(), None);
+		assert_eq!(f64::INFINITY.integral(), None);
+		assert_eq!(f32::NEG_INFINITY.integral(), None);
+		assert_eq!((-3.0f64).integral(), Some(-3.0));
+		assert_eq!(f64::MAX.integral(), Some(f64::MAX));
+		assert_eq!(2.5f32.integral(), None);
+	}
+
+	#[test]
+	fn to_non_zero() {
+		assert_eq!(0u8.to_non_zero(), None);
+		assert_eq!(0i64.to_non_zero(), None);
+		assert_eq!(7u32.to_non_zero(), NonZero::new(7));
+		assert_eq!((-7i16).to_non_zero(), NonZero::new(-7));
+		assert_eq!(i128::MIN.to_non_zero(), NonZero::new(i128::MIN));
+		assert_eq!(usize::MAX.to_non_zero().map(NonZero::get), Some(usize::MAX));
+	}
+
+	#[test]
+	fn non_zero_filters() {
+		let four = NonZero::new(4u8).unwrap();
+		assert_eq!(four.even(), Some(four));
+		assert_eq!(four.odd(), None);
+		assert_eq!(four.in_range(1..4), None);
+		assert_eq!(four.in_range(4..), Some(four));
+
+		let minus_three = NonZero::new(-3i32).unwrap();
+		assert!(minus_three.is_odd());
+		assert_eq!(minus_three.in_range(-3..=-1), Some(minus_three));
+		assert_eq!(minus_three.in_range(0..), None);
+
+		let min = NonZero::new(i64::MIN).unwrap();
+		assert!(min.is_even());
+		assert_eq!(min.in_range(..0), Some(min));
+	}
+
+	#[cfg(feature = "primes")]
+	#[test]
+	fn non_zero_prime() {
+		assert_eq!(NonZero::new(13u16).unwrap().prime().map(NonZero::get), Some(13));
+		assert_eq!(NonZero::new(1u64).unwrap().prime(), None);
+		assert_eq!(NonZero::new(-13i8).unwrap().prime(), None);
+		assert!(NonZero::new(u128::MAX - 158).unwrap().is_prime());
+	}
+
+	#[test]
+	fn div_rounding() {
+		assert_eq!(7u8.checked_div_round_up(2), Some(4));
+		assert_eq!(7u8.checked_div_round_down(2), Some(3));
+		assert_eq!(u8::MAX.checked_div_round_up(2), Some(128));
+		assert_eq!(7u8.checked_div_round_up(0), None);
+
+		assert_eq!((-7i32).checked_div_round_up(2), Some(-3));
+		assert_eq!((-7i32).checked_div_round_down(2), Some(-4));
+		assert_eq!(7i32.checked_div_round_up(-2), Some(-3));
+		assert_eq!(7i32.checked_div_round_down(-2), Some(-4));
+		assert_eq!((-7i32).checked_div_round_up(-2), Some(4));
+		assert_eq!((-7i32).checked_div_round_down(-2), Some(3));
+		assert_eq!((-6i32).checked_div_round_down(2), Some(-3));
+		assert_eq!((-6i32).checked_div_round_up(-2), Some(3));
+
+		assert_eq!(i8::MIN.checked_div_round_up(-1), None);
+		assert_eq!(i8::MIN.checked_div_round_down(-1), None);
+		assert_eq!(i128::MIN.checked_div_round_down(-1), None);
+		assert_eq!(i8::MIN.checked_div_round_up(-3), Some(43));
+		assert_eq!(i8::MIN.checked_div_round_down(3), Some(-43));
+		assert_eq!(i8::MAX.checked_div_round_up(i8::MIN), Some(0));
+		assert_eq!(i8::MAX.checked_div_round_down(i8::MIN), Some(-1));
+		assert_eq!((-7i32).div_round_down(2), -4);
+	}
+
+	#[test]
+	#[should_panic = "divisor is zero or division overflowed"]
+	fn div_round_up_overflow() {
+		i16::MIN.div_round_up(-1);
+	}
+
+	#[test]
+	fn round_to_multiple() {
+		assert_eq!(13u32.checked_round_up_to(4), Some(16));
+		assert_eq!(13u32.checked_round_down_to(4), Some(12));
+		assert_eq!(16u32.checked_round_up_to(4), Some(16));
+		assert_eq!(13u32.checked_round_up_to(0), None);
+		assert_eq!(u8::MAX.checked_round_up_to(2), None);
+
+		assert_eq!((-13i32).checked_round_up_to(4), Some(-12));
+		assert_eq!((-13i32).checked_round_down_to(4), Some(-16));
+		assert_eq!(13i32.checked_round_up_to(-4), Some(16));
+		assert_eq!((-13i32).checked_round_down_to(-4), Some(-16));
+		assert_eq!(i8::MIN.checked_round_up_to(-1), Some(i8::MIN));
+		assert_eq!(i8::MIN.checked_round_down_to(-1), Some(i8::MIN));
+		assert_eq!(i8::MIN.checked_round_down_to(3), None);
+		assert_eq!(i8::MIN.checked_round_up_to(i8::MIN), Some(i8::MIN));
+		assert_eq!(i8::MAX.checked_round_up_to(i8::MIN), None);
+		assert_eq!(1i8.checked_round_down_to(i8::MIN), Some(0));
+	}
+
+	#[test]
+	fn divisible_by() {
+		assert!(12u8.is_divisible_by(4));
+		assert!(!12u8.is_divisible_by(5));
+		assert!(0u8.is_divisible_by(0));
+		assert!(!1u8.is_divisible_by(0));
+		assert!((-12i8).is_divisible_by(-4));
+		assert!(i8::MIN.is_divisible_by(-1));
+		assert!(i128::MIN.is_divisible_by(i128::MIN));
+		assert_eq!((-9i64).divisible_by(3), Some(-9));
+		assert_eq!(i64::MIN.divisible_by(3), None);
+	}
+
+	#[test]
+	fn align_up_pow2() {
+		assert_eq!(13u32.checked_align_up_pow2(8), Some(16));
+		assert_eq!(16u32.checked_align_up_pow2(8), Some(16));
+		assert_eq!(13u32.checked_align_up_pow2(6), None);
+		assert_eq!(13u32.checked_align_up_pow2(0), None);
+		assert_eq!((-13i32).checked_align_up_pow2(8), Some(-8));
+		assert_eq!(13i32.checked_align_up_pow2(-8), None);
+		assert_eq!(i8::MIN.checked_align_up_pow2(64), Some(i8::MIN));
+		assert_eq!(u8::MAX.checked_align_up_pow2(2), None);
+		assert_eq!(u128::MAX.checked_align_up_pow2(1), Some(u128::MAX));
+	}
+
+	#[test]
+	fn cast_checked() {
+		assert_eq!(200u8.cast_checked::<i8>(), None);
+		assert_eq!(100u8.cast_checked::<i8>(), Some(100));
+		assert_eq!((-1i8).cast_checked::<u8>(), None);
+		assert_eq!((-1i8).cast_checked::<u128>(), None);
+		assert_eq!((-1i8).cast_checked::<i128>(), Some(-1));
+		assert_eq!(i128::MIN.cast_checked::<i64>(), None);
+		assert_eq!(i64::MIN.cast_checked::<i128>(), Some(i64::MIN as i128));
+		assert_eq!(u128::MAX.cast_checked::<i128>(), None);
+		assert_eq!(i128::MAX.cast_checked::<u128>(), Some(i128::MAX as u128));
+		assert_eq!(u64::MAX.cast_checked::<usize>().is_some(), usize::BITS >= 64);
+		assert_eq!(0u128.cast_checked::<i8>(), Some(0));
+	}
+
+	#[test]
+	fn cast_saturating() {
+		assert_eq!(300u16.cast_saturating::<u8>(), u8::MAX);
+		assert_eq!(300u16.cast_saturating::<i8>(), i8::MAX);
+		assert_eq!((-300i16).cast_saturating::<i8>(), i8::MIN);
+		assert_eq!((-1i32).cast_saturating::<u64>(), 0);
+		assert_eq!(i128::MIN.cast_saturating::<u128>(), 0);
+		assert_eq!(u128::MAX.cast_saturating::<i128>(), i128::MAX);
+		assert_eq!(i128::MIN.cast_saturating::<i8>(), i8::MIN);
+		assert_eq!(42u128.cast_saturating::<i8>(), 42);
+	}
+
+	#[test]
+	fn cast_wrapping() {
+		assert_eq!(300u16.cast_wrapping::<u8>(), 300u16 as u8);
+		assert_eq!((-1i8).cast_wrapping::<u32>(), u32::MAX);
+		assert_eq!((-1i8).cast_wrapping::<u128>(), u128::MAX);
+		assert_eq!(u128::MAX.cast_wrapping::<i8>(), -1);
+		assert_eq!(i128::MIN.cast_wrapping::<u128>(), 1 << 127);
+		assert_eq!(200u8.cast_wrapping::<i8>(), 200u8 as i8);
+		assert_eq!(i64::MIN.cast_wrapping::<i32>(), 0);
+	}
+
+	#[test]
+	fn widen() {
+		assert_eq!(u8::MAX.widen::<u16>(), 255);
+		assert_eq!(u8::MAX.widen::<i16>(), 255);
+		assert_eq!(i8::MIN.widen::<i128>(), -128);
+		assert_eq!(u64::MAX.widen::<u128>(), u64::MAX as u128);
+		assert_eq!(u16::MAX.widen::<usize>(), 65_535);
+	}
+}
@@ -0,0 +1,403 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Overflow-free integer arithmetic over `u128`, shared by [`NumExt`][] methods.
+//!
+//! [`NumExt`]: super::NumExt
+
+use std::ops::{Bound, RangeBounds};
+
+/// Computes `a + b (mod m)` without overflowing, where `a` and `b` are reduced.
+pub fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+	let (sum, overflow) = a.overflowing_add(b);
+	if overflow || sum >= m {
+		sum.wrapping_sub(m)
+	} else {
+		sum
+	}
+}
+
+/// Computes `a - b (mod m)`, where `a` and `b` are reduced.
+pub fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+	if a >= b {
+		a - b
+	} else {
+		m - (b - a)
+	}
+}
+
+/// Computes `a * b (mod m)` without overflowing, where `a` and `b` are reduced.
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+	if let Some(product) = a.checked_mul(b) {
+		return product % m
+	}
+
+	let (mut a, mut b, mut product) = (a, b, 0);
+	while b > 0 {
+		if b & 1 == 1 {
+			product = add_mod(product, a, m);
+		}
+		a = add_mod(a, a, m);
+		b >>= 1;
+	}
+	product
+}
+
+/// Computes `base ^ exp (mod m)`.
+pub fn pow_mod(mut base: u128, mut exp: u128, m: u128) -> u128 {
+	let mut result = 1 % m;
+	base %= m;
+	while exp > 0 {
+		if exp & 1 == 1 {
+			result = mul_mod(result, base, m);
+		}
+		base = mul_mod(base, base, m);
+		exp >>= 1;
+	}
+	result
+}
+
+/// Computes the greatest common divisor of `a` and `b`.
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+	a
+}
+
+/// Returns the magnitude of any integer as a `u128`.
+pub fn magnitude<T: Copy>(n: T) -> u128 where i128: TryFrom<T>, u128: TryFrom<T> {
+	match i128::try_from(n) {
+		Ok(n) => n.unsigned_abs(),
+		// Only unsigned values above i128::MAX fail to convert.
+		Err(_) => u128::try_from(n).unwrap_or(u128::MAX)
+	}
+}
+
+/// Computes the greatest common divisor `g` of `a` and `b`, with Bézout
+/// coefficients `x` and `y` such that `ax + by = g`. The coefficients are the
+/// minimal pair, bounded by `b / 2g` and `a / 2g`, so they always fit in `i128`.
+pub fn extended_gcd(a: u128, b: u128) -> (u128, i128, i128) {
+	let (mut r0, mut r1) = (a, b);
+	let (mut x0, mut x1) = (1i128, 0i128);
+	let (mut y0, mut y1) = (0i128, 1i128);
+	while r1 != 0 {
+		let q = r0 / r1;
+		(r0, r1) = (r1, r0 - q * r1);
+		// Intermediate products may leave the range of i128, but every coefficient
+		// we keep fits, so wrapping arithmetic yields it exactly.
+		(x0, x1) = (x1, x0.wrapping_sub((q as i128).wrapping_mul(x1)));
+		(y0, y1) = (y1, y0.wrapping_sub((q as i128).wrapping_mul(y1)));
+	}
+	(r0, x0, y0)
+}
+
+/// Computes the multiplicative inverse of `a (mod m)`, where `a` is reduced and
+/// `m` is positive, or `None` if `a` and `m` aren't coprime.
+pub fn inv_mod(a: u128, m: u128) -> Option<u128> {
+	let (mut r0, mut r1) = (m, a);
+	let (mut t0, mut t1) = (0, 1 % m);
+	while r1 != 0 {
+		let q = r0 / r1;
+		(r0, r1) = (r1, r0 - q * r1);
+		(t0, t1) = (t1, sub_mod(t0, mul_mod(q % m, t1, m), m));
+	}
+	(r0 == 1).then_some(t0)
+}
+
+/// Returns the exponent `k` such that `base ^ k = n`, if there is one. Zero is
+/// only a power of zero, and one is the zeroth power of anything.
+pub fn log_exact(mut n: u128, base: u128) -> Option<u32> {
+	match (n, base) {
+		(1, _) => Some(0),
+		(0, 0) => Some(1),
+		(_, 0 | 1) | (0, _) => None,
+		_ => {
+			let mut k = 0;
+			while n.is_multiple_of(base) {
+				n /= base;
+				k += 1;
+			}
+			(n == 1).then_some(k)
+		}
+	}
+}
+
+/// Returns `true` if `n`, with sign `n_negative`, is an integer power of `base`,
+/// with sign `base_negative`.
+pub fn is_power_of(n: u128, n_negative: bool, base: u128, base_negative: bool) -> bool {
+	if n == 1 && base == 1 {
+		// One is the zeroth power of anything, negative one an odd power of itself.
+		return !n_negative || base_negative
+	}
+
+	match log_exact(n, base) {
+		Some(k) if base_negative => (k % 2 == 1) == n_negative,
+		Some(_) => !n_negative,
+		None => false
+	}
+}
+
+/// Returns `true` if any integer is negative.
+pub fn is_negative<T>(n: T) -> bool where i128: TryFrom<T> {
+	i128::try_from(n).is_ok_and(i128::is_negative)
+}
+
+/// Returns the least non-negative residue of any integer modulo positive `m`.
+pub fn residue<T: Copy>(n: T, m: u128) -> u128 where i128: TryFrom<T>, u128: TryFrom<T> {
+	let remainder = magnitude(n) % m;
+	if is_negative(n) && remainder != 0 {
+		m - remainder
+	} else {
+		remainder
+	}
+}
+
+/// Converts a magnitude and sign back into any integer, or `None` if it doesn't
+/// fit.
+pub fn from_magnitude<T: TryFrom<u128> + TryFrom<i128>>(magnitude: u128, negative: bool) -> Option<T> {
+	if negative {
+		0i128.checked_sub_unsigned(magnitude)?.try_into().ok()
+	} else {
+		magnitude.try_into().ok()
+	}
+}
+
+/// Maps any integer onto `u128` keys, preserving order, with `min` mapping to zero.
+pub fn to_key<T: Copy>(n: T, min: T) -> u128 where i128: TryFrom<T>, u128: TryFrom<T> {
+	let offset = magnitude(min);
+	if is_negative(n) {
+		offset - magnitude(n)
+	} else {
+		offset + magnitude(n)
+	}
+}
+
+/// Maps a key produced by [`to_key`] back to its integer.
+pub fn from_key<T>(key: u128, min: T) -> Option<T>
+	where T: Copy + TryFrom<u128> + TryFrom<i128>, i128: TryFrom<T>, u128: TryFrom<T> {
+	let offset = magnitude(min);
+	if key >= offset {
+		from_magnitude(key - offset, false)
+	} else {
+		from_magnitude(offset - key, true)
+	}
+}
+
+/// Resolves `range` into inclusive bounds in key space, or `None` if the range is
+/// empty.
+pub fn key_bounds<T: Copy>(range: &impl RangeBounds<T>, min: T, max: T) -> Option<(u128, u128)>
+	where i128: TryFrom<T>, u128: TryFrom<T> {
+	let lower = match range.start_bound() {
+		Bound::Included(&start) => to_key(start, min),
+		Bound::Excluded(&start) => to_key(start, min).checked_add(1)?,
+		Bound::Unbounded => 0
+	};
+	let upper = match range.end_bound() {
+		Bound::Included(&end) => to_key(end, min),
+		Bound::Excluded(&end) => to_key(end, min).checked_sub(1)?,
+		Bound::Unbounded => to_key(max, min)
+	};
+	(lower <= upper).then_some((lower, upper))
+}
+
+/// Wraps `key` into `lower..=upper` modulo the width of the range.
+pub fn wrap_key(key: u128, lower: u128, upper: u128) -> u128 {
+	// The width only overflows when the range covers every key.
+	let Some(width) = (upper - lower).checked_add(1) else { return key };
+	let offset = if key >= lower {
+		(key - lower) % width
+	} else {
+		(width - (lower - key) % width) % width
+	};
+	lower + offset
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ops::Bound;
+	use super::*;
+	use crate::NumExt;
+
+	/// Checks Bézout's identity for `extended_gcd`, modulo 2¹²⁸.
+	fn check_bezout(a: i128, b: i128, (g, x, y): (u128, i128, i128)) {
+		let sum = a.wrapping_mul(x).wrapping_add(b.wrapping_mul(y));
+		assert_eq!(sum as u128, g, "{a} * {x} + {b} * {y} = {g}");
+	}
+
+	#[test]
+	fn gcd_and_lcm() {
+		assert_eq!(0u8.gcd(0), 0);
+		assert_eq!(i8::MIN.gcd(0), 128);
+		assert_eq!(i8::MIN.gcd(i8::MIN), 128);
+		assert_eq!((-12i32).gcd(18), 6);
+		assert_eq!(u128::MAX.gcd(u128::MAX - 1), 1);
+		assert_eq!(4u8.lcm(6), Some(12));
+		assert_eq!((-4i8).lcm(-6), Some(12));
+		assert_eq!(i8::MIN.lcm(3), None);
+		assert_eq!(i8::MIN.lcm(2), Some(128));
+		assert_eq!(u128::MAX.lcm(2), None);
+		assert_eq!(0u64.lcm(u64::MAX), Some(0));
+	}
+
+	#[test]
+	fn extended_gcd() {
+		assert_eq!(240u32.extended_gcd(46), (2, -9, 47));
+		assert_eq!((-240i32).extended_gcd(46), (2, 9, 47));
+		assert_eq!(0u8.extended_gcd(0), (0, 1, 0));
+		assert_eq!(0i8.extended_gcd(-5), (5, 0, -1));
+
+		let (g, x, y) = i8::MIN.extended_gcd(i8::MAX);
+		assert_eq!(g, 1);
+		check_bezout(-128, 127, (1, x, y));
+
+		let (g, x, y) = i128::MIN.extended_gcd(i128::MAX);
+		assert_eq!(g, 1);
+		check_bezout(i128::MIN, i128::MAX, (1, x, y));
+
+		let result = u128::MAX.extended_gcd(u128::MAX - 1);
+		assert_eq!(result, (1, 1, -1));
+		let result = u128::MAX.extended_gcd(1 << 127);
+		assert_eq!(result.0, 1);
+		check_bezout(-1, i128::MIN, result);
+	}
+
+	#[test]
+	fn pow_mod() {
+		assert_eq!(4u8.pow_mod(13, 0), None);
+		assert_eq!(4i8.pow_mod(13, -5), None);
+		assert_eq!(4u32.pow_mod(13, 497), Some(445));
+		assert_eq!(7u8.pow_mod(0, 1), Some(0));
+		assert_eq!(7u8.pow_mod(0, 2), Some(1));
+		assert_eq!((-2i8).pow_mod(3, 5), Some(2));
+		assert_eq!((-2i8).pow_mod(2, 5), Some(4));
+		assert_eq!(i8::MIN.pow_mod(1, i8::MAX), Some(126));
+		assert_eq!(i128::MIN.pow_mod(u128::MAX, i128::MAX), Some(i128::MAX - 1));
+		assert_eq!(u128::MAX.pow_mod(2, u128::MAX), Some(0));
+		assert_eq!((u128::MAX - 1).pow_mod(u128::MAX, u128::MAX), Some(u128::MAX - 1));
+		// By Fermat's little theorem the exponent reduces modulo 2¹²⁷ - 2, to 2.
+		assert_eq!(3u128.pow_mod(u128::MAX - 1, (1 << 127) - 1), Some(9));
+	}
+
+	#[test]
+	fn inv_mod() {
+		assert_eq!(3u8.inv_mod(7), Some(5));
+		assert_eq!((-3i8).inv_mod(7), Some(2));
+		assert_eq!(6u8.inv_mod(9), None);
+		assert_eq!(0u8.inv_mod(7), None);
+		assert_eq!(5u8.inv_mod(1), Some(0));
+		assert_eq!(5i8.inv_mod(0), None);
+		assert_eq!(5i8.inv_mod(-7), None);
+		assert_eq!(i8::MIN.inv_mod(i8::MAX), Some(i8::MAX - 1));
+		assert_eq!(i128::MIN.inv_mod(i128::MAX), Some(i128::MAX - 1));
+		assert_eq!(2u128.inv_mod(u128::MAX), Some(1 << 127));
+		assert_eq!((u128::MAX - 1).inv_mod(u128::MAX), Some(u128::MAX - 1));
+		assert_eq!(3u128.inv_mod(u128::MAX), None);
+	}
+
+	#[test]
+	fn is_power_of() {
+		assert!(8u8.is_power_of(2));
+		assert!(1u8.is_power_of(0));
+		assert!(1u8.is_power_of(7));
+		assert!(0u8.is_power_of(0));
+		assert!(!0u8.is_power_of(2));
+		assert!(!5u8.is_power_of(1));
+		assert!(!6u8.is_power_of(2));
+		assert!((1u128 << 127).is_power_of(2));
+		assert!(u128::MAX.is_power_of(u128::MAX));
+		assert!(!u128::MAX.is_power_of(2));
+
+		assert!((-8i32).is_power_of(-2));
+		assert!(16i32.is_power_of(-2));
+		assert!(!(-16i32).is_power_of(-2));
+		assert!(!(-8i32).is_power_of(2));
+		assert!((-1i8).is_power_of(-1));
+		assert!(1i8.is_power_of(-1));
+		assert!(!(-1i8).is_power_of(1));
+		assert!(i8::MIN.is_power_of(-2));
+		assert!(!i8::MIN.is_power_of(2));
+		assert!(i128::MIN.is_power_of(-2));
+		assert!(!i128::MIN.is_power_of(i128::MIN.wrapping_add(1)));
+	}
+
+	#[test]
+	fn keys() {
+		assert_eq!(to_key(i8::MIN, i8::MIN), 0);
+		assert_eq!(to_key(-1i8, i8::MIN), 127);
+		assert_eq!(to_key(i8::MAX, i8::MIN), 255);
+		assert_eq!(to_key(i128::MIN, i128::MIN), 0);
+		assert_eq!(to_key(i128::MAX, i128::MIN), u128::MAX);
+		assert_eq!(to_key(u128::MAX, 0), u128::MAX);
+		assert_eq!(from_key(u128::MAX, i128::MIN), Some(i128::MAX));
+		assert_eq!(from_key(0, i128::MIN), Some(i128::MIN));
+		assert_eq!(from_key(1 << 127, i128::MIN), Some(0));
+		assert_eq!(from_key(256, i8::MIN), None::<i8>);
+	}
+
+	#[test]
+	fn key_bounds() {
+		assert_eq!(super::key_bounds(&(..), i8::MIN, i8::MAX), Some((0, 255)));
+		assert_eq!(super::key_bounds(&(-1i8..1), i8::MIN, i8::MAX), Some((127, 128)));
+		assert_eq!(super::key_bounds(&(-1i8..=1), i8::MIN, i8::MAX), Some((127, 129)));
+		assert_eq!(super::key_bounds(&(5u8..5), 0, u8::MAX), None);
+		assert_eq!(super::key_bounds(&(Bound::Included(6u8), Bound::Included(5)), 0, u8::MAX), None);
+		assert_eq!(super::key_bounds(&(..0u8), 0, u8::MAX), None);
+		assert_eq!(super::key_bounds(&(Bound::Excluded(u8::MAX), Bound::Unbounded), 0, u8::MAX), None);
+		assert_eq!(super::key_bounds(&(Bound::Excluded(u128::MAX), Bound::Unbounded), 0, u128::MAX), None);
+		assert_eq!(super::key_bounds(&(..), i128::MIN, i128::MAX), Some((0, u128::MAX)));
+		assert_eq!(super::key_bounds(&(i128::MIN..), i128::MIN, i128::MAX), Some((0, u128::MAX)));
+	}
+
+	#[test]
+	fn wrap_key() {
+		assert_eq!(super::wrap_key(7, 2, 4), 4);
+		assert_eq!(super::wrap_key(0, 2, 4), 3);
+		assert_eq!(super::wrap_key(1, 2, 4), 4);
+		assert_eq!(super::wrap_key(3, 3, 3), 3);
+		assert_eq!(super::wrap_key(u128::MAX, 0, u128::MAX), u128::MAX);
+		assert_eq!(super::wrap_key(u128::MAX, 1, u128::MAX), u128::MAX);
+		assert_eq!(super::wrap_key(0, 1, u128::MAX), u128::MAX);
+		assert_eq!(super::wrap_key(0, u128::MAX, u128::MAX), u128::MAX);
+	}
+
+	#[test]
+	fn clamp_to() {
+		assert_eq!(300u16.clamp_to(..256), Some(255));
+		assert_eq!(5u8.clamp_to(10..), Some(10));
+		assert_eq!(5u8.clamp_to(..), Some(5));
+		assert_eq!(5u8.clamp_to(3..3), None);
+		assert_eq!(5u8.clamp_to(..0), None);
+		assert_eq!((-50i8).clamp_to(-10..=10), Some(-10));
+		assert_eq!(50i8.clamp_to(-10..10), Some(9));
+		assert_eq!(0i8.clamp_to((Bound::Excluded(-3), Bound::Excluded(-1))), Some(-2));
+		assert_eq!(0i8.clamp_to((Bound::Excluded(-2), Bound::Excluded(-1))), None);
+		assert_eq!(i8::MIN.clamp_to((Bound::Excluded(i8::MIN), Bound::Unbounded)), Some(i8::MIN + 1));
+		assert_eq!(i128::MIN.clamp_to(..), Some(i128::MIN));
+		assert_eq!(i128::MAX.clamp_to(..0), Some(-1));
+		assert_eq!(i128::MIN.clamp_to(0..), Some(0));
+		assert_eq!(u128::MAX.clamp_to(..u128::MAX), Some(u128::MAX - 1));
+		assert_eq!(0u128.clamp_to((Bound::Excluded(u128::MAX), Bound::Unbounded)), None);
+	}
+
+	#[test]
+	fn wrap_into() {
+		assert_eq!(10u8.wrap_into(0..8), Some(2));
+		assert_eq!(10u8.wrap_into(0..=8), Some(1));
+		assert_eq!(10u8.wrap_into(12..15), Some(13));
+		assert_eq!(10u8.wrap_into(4..4), None);
+		assert_eq!(10u8.wrap_into(..0), None);
+		assert_eq!(200u8.wrap_into(..), Some(200));
+		assert_eq!(0u8.wrap_into(250..), Some(252));
+		assert_eq!((-1i32).wrap_into(0..8), Some(7));
+		assert_eq!((-9i32).wrap_into(0..8), Some(7));
+		assert_eq!(9i32.wrap_into(-4..4), Some(1));
+		assert_eq!(i8::MIN.wrap_into(0..10), Some(2));
+		assert_eq!(i8::MAX.wrap_into(-10..0), Some(-3));
+		assert_eq!(i128::MIN.wrap_into(..), Some(i128::MIN));
+		assert_eq!(i128::MAX.wrap_into(i128::MIN..0), Some(-1));
+		assert_eq!(i128::MIN.wrap_into(0..), Some(0));
+		assert_eq!(u128::MAX.wrap_into(..u128::MAX), Some(0));
+		assert_eq!(u128::MAX.wrap_into(1..), Some(u128::MAX));
+		assert_eq!(0u128.wrap_into(1..), Some(u128::MAX));
+	}
+}
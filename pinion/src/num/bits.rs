@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bit manipulation for [`Int`] types.
+
+use std::iter::FusedIterator;
+use std::ops::{Bound, RangeBounds};
+use super::{Int, NumExt};
+
+pub trait BitsExt: Int {
+	/// Returns `true` if bit `n` is set, counting from the least significant bit.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not less than [`BITS`](Int::BITS).
+	fn bit(self, n: u32) -> bool {
+		check_index::<Self>(n);
+		self & Self::ONE << n != Self::ZERO
+	}
+	/// Returns this number with bit `n` set.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not less than [`BITS`](Int::BITS).
+	fn set_bit(self, n: u32) -> Self {
+		check_index::<Self>(n);
+		self | Self::ONE << n
+	}
+	/// Returns this number with bit `n` cleared.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not less than [`BITS`](Int::BITS).
+	fn clear_bit(self, n: u32) -> Self {
+		check_index::<Self>(n);
+		self & !(Self::ONE << n)
+	}
+	/// Returns this number with bit `n` flipped.
+	///
+	/// # Panics
+	///
+	/// Panics if `n` is not less than [`BITS`](Int::BITS).
+	fn toggle_bit(self, n: u32) -> Self {
+		check_index::<Self>(n);
+		self ^ Self::ONE << n
+	}
+	/// Returns the bits within `range`, shifted down to the least significant bit.
+	/// An empty range, even one starting at [`BITS`](Int::BITS), extracts zero.
+	/// Named apart from the unstable inherent `extract_bits`, which takes a mask.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is decreasing or extends past [`BITS`](Int::BITS).
+	fn bits_in<R: RangeBounds<u32>>(self, range: R) -> Self {
+		let (start, width) = field::<Self>(range);
+		Self::from_bits(raw_bits(self).checked_shr(start).unwrap_or(0) & mask(width))
+	}
+	/// Returns this number with the bits within `range` replaced by the least
+	/// significant bits of `value`. Bits of `value` which don't fit are ignored, so
+	/// an empty range leaves this number unchanged.
+	///
+	/// # Panics
+	///
+	/// Panics if `range` is decreasing or extends past [`BITS`](Int::BITS).
+	fn with_bits_in<R: RangeBounds<u32>>(self, range: R, value: Self) -> Self {
+		let (start, width) = field::<Self>(range);
+		let shift = |bits: u128| bits.checked_shl(start).unwrap_or(0);
+		let field = shift(mask(width));
+		Self::from_bits(raw_bits(self) & !field | shift(raw_bits(value)) & field)
+	}
+	/// Returns an iterator over the indices of set bits, from least to most
+	/// significant.
+	fn iter_set_bits(self) -> SetBits {
+		SetBits(raw_bits(self))
+	}
+	/// Returns the index of the least significant set bit, or `None` if no bits are
+	/// set.
+	fn lowest_set(self) -> Option<u32> {
+		self.iter_set_bits().next()
+	}
+	/// Returns the index of the most significant set bit, or `None` if no bits are
+	/// set.
+	fn highest_set(self) -> Option<u32> {
+		self.iter_set_bits().next_back()
+	}
+	/// Returns the smallest power of two greater than or equal to this number, or
+	/// `None` if it overflows. Numbers less than one, including negative numbers,
+	/// round up to one.
+	fn next_power_of_two_checked(self) -> Option<Self> {
+		if self <= Self::ONE {
+			return Some(Self::ONE)
+		}
+
+		raw_bits(self).checked_next_power_of_two()?.cast_checked()
+	}
+}
+
+impl<T: Int> BitsExt for T { }
+
+/// An iterator over the indices of set bits in an integer, created by
+/// [`BitsExt::iter_set_bits`].
+#[derive(Clone, Debug)]
+pub struct SetBits(u128);
+
+impl Iterator for SetBits {
+	type Item = u32;
+
+	fn next(&mut self) -> Option<u32> {
+		let index = self.0.non_zero()?.trailing_zeros();
+		self.0 &= self.0 - 1;
+		Some(index)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.0.count_ones() as usize;
+		(len, Some(len))
+	}
+}
+
+impl DoubleEndedIterator for SetBits {
+	fn next_back(&mut self) -> Option<u32> {
+		let index = u128::BITS - 1 - self.0.non_zero()?.leading_zeros();
+		self.0 ^= 1 << index;
+		Some(index)
+	}
+}
+
+impl ExactSizeIterator for SetBits { }
+
+impl FusedIterator for SetBits { }
+
+/// Returns the bits of `n`, zero-extended to 128 bits.
+fn raw_bits<T: Int>(n: T) -> u128 {
+	n.to_bits().1 & mask(T::BITS)
+}
+
+/// Returns a mask of the lowest `width` bits.
+fn mask(width: u32) -> u128 {
+	u128::MAX.checked_shr(u128::BITS - width).unwrap_or(0)
+}
+
+fn check_index<T: Int>(n: u32) {
+	assert!(n < T::BITS, "bit index {n} is out of range for a {}-bit integer", T::BITS);
+}
+
+/// Resolves `range` into a start index and width. Bounds at `u32::MAX` saturate
+/// rather than overflow, which puts them out of range.
+fn field<T: Int>(range: impl RangeBounds<u32>) -> (u32, u32) {
+	let start = match range.start_bound() {
+		Bound::Included(&start) => start,
+		Bound::Excluded(&start) => start.saturating_add(1),
+		Bound::Unbounded => 0
+	};
+	let end = match range.end_bound() {
+		Bound::Included(&end) => end.saturating_add(1),
+		Bound::Excluded(&end) => end,
+		Bound::Unbounded => T::BITS
+	};
+	assert!(
+		start <= end && end <= T::BITS,
+		"bit range {start}..{end} is out of range for a {}-bit integer",
+		T::BITS
+	);
+	(start, end - start)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn single_bits() {
+		assert!(0b100u8.bit(2));
+		assert!(!0b100u8.bit(1));
+		assert!(i8::MIN.bit(7));
+		assert!((-1i128).bit(127));
+		assert_eq!(0u8.set_bit(7), 0x80);
+		assert_eq!(0i8.set_bit(7), i8::MIN);
+		assert_eq!(u128::MAX.clear_bit(127), u128::MAX >> 1);
+		assert_eq!(0b101u16.toggle_bit(0).toggle_bit(1), 0b110);
+	}
+
+	#[test]
+	#[should_panic = "bit index 8 is out of range for a 8-bit integer"]
+	fn bit_out_of_range() {
+		0u8.bit(8);
+	}
+
+	#[test]
+	fn bits_in() {
+		assert_eq!(0xABCDu16.bits_in(4..12), 0xBC);
+		assert_eq!(0xABCDu16.bits_in(12..), 0xA);
+		assert_eq!(0xABCDu16.bits_in(..=3), 0xD);
+		assert_eq!(0xABCDu16.bits_in(..), 0xABCD);
+		assert_eq!((-1i8).bits_in(1..), 0x7F);
+		assert_eq!((-1i8).bits_in(..), -1);
+		assert_eq!(u128::MAX.bits_in(64..), u64::MAX as u128);
+		assert_eq!(u128::MAX.bits_in(..), u128::MAX);
+		assert_eq!(5u128.bits_in(128..), 0);
+		assert_eq!(5u8.bits_in(8..8), 0);
+		assert_eq!(5u8.bits_in(3..3), 0);
+		assert_eq!(5u8.bits_in((Bound::Excluded(6), Bound::Unbounded)), 0);
+		assert_eq!(5u8.bits_in((Bound::Excluded(7), Bound::Unbounded)), 0);
+	}
+
+	#[test]
+	fn with_bits_in() {
+		assert_eq!(0xABCDu16.with_bits_in(4..12, 0x12), 0xA12D);
+		assert_eq!(0xABCDu16.with_bits_in(4..12, 0xF12), 0xA12D);
+		assert_eq!(0u8.with_bits_in(7.., 1), 0x80);
+		assert_eq!(0i8.with_bits_in(7.., 1), i8::MIN);
+		assert_eq!(0i8.with_bits_in(.., -1), -1);
+		assert_eq!(0u128.with_bits_in(127.., 1), 1 << 127);
+		assert_eq!(0u128.with_bits_in(.., u128::MAX), u128::MAX);
+		assert_eq!(5u128.with_bits_in(128.., 1), 5);
+		assert_eq!(5u8.with_bits_in(2..2, 1), 5);
+	}
+
+	#[test]
+	#[should_panic = "bit range 4..9 is out of range for a 8-bit integer"]
+	fn field_past_end() {
+		0u8.bits_in(4..9);
+	}
+
+	#[test]
+	#[should_panic = "out of range for a 8-bit integer"]
+	fn field_decreasing() {
+		#[allow(clippy::reversed_empty_ranges)]
+		0u8.bits_in(4..2);
+	}
+
+	#[test]
+	#[should_panic = "out of range for a 128-bit integer"]
+	fn field_excluded_start_at_max() {
+		0u128.bits_in((Bound::Excluded(u32::MAX), Bound::Unbounded));
+	}
+
+	#[test]
+	#[should_panic = "out of range for a 32-bit integer"]
+	fn field_included_end_at_max() {
+		0u32.with_bits_in(..=u32::MAX, 1);
+	}
+
+	#[test]
+	fn set_bits() {
+		assert_eq!(0b1010_0110u8.iter_set_bits().collect::<Vec<_>>(), [1, 2, 5, 7]);
+		assert_eq!(0b1010_0110u8.iter_set_bits().rev().collect::<Vec<_>>(), [7, 5, 2, 1]);
+		assert_eq!((-1i16).iter_set_bits().len(), 16);
+		assert_eq!(i64::MIN.iter_set_bits().collect::<Vec<_>>(), [63]);
+		assert_eq!(0u32.iter_set_bits().next(), None);
+
+		let mut bits = 0b1011u8.iter_set_bits();
+		assert_eq!(bits.next(), Some(0));
+		assert_eq!(bits.next_back(), Some(3));
+		assert_eq!(bits.next(), Some(1));
+		assert_eq!(bits.next_back(), None);
+	}
+
+	#[test]
+	fn lowest_and_highest_set() {
+		assert_eq!(0u8.lowest_set(), None);
+		assert_eq!(0u8.highest_set(), None);
+		assert_eq!(0b0110_0000u8.lowest_set(), Some(5));
+		assert_eq!(0b0110_0000u8.highest_set(), Some(6));
+		assert_eq!((-1i8).highest_set(), Some(7));
+		assert_eq!((-2i128).lowest_set(), Some(1));
+		assert_eq!(u128::MAX.highest_set(), Some(127));
+	}
+
+	#[test]
+	fn next_power_of_two_checked() {
+		assert_eq!(0u8.next_power_of_two_checked(), Some(1));
+		assert_eq!(5u8.next_power_of_two_checked(), Some(8));
+		assert_eq!(128u8.next_power_of_two_checked(), Some(128));
+		assert_eq!(129u8.next_power_of_two_checked(), None);
+		assert_eq!((-5i8).next_power_of_two_checked(), Some(1));
+		assert_eq!(i8::MIN.next_power_of_two_checked(), Some(1));
+		assert_eq!(64i8.next_power_of_two_checked(), Some(64));
+		assert_eq!(65i8.next_power_of_two_checked(), None);
+		assert_eq!(i128::MAX.next_power_of_two_checked(), None);
+		assert_eq!((1u128 << 127).next_power_of_two_checked(), Some(1 << 127));
+		assert_eq!(u128::MAX.next_power_of_two_checked(), None);
+	}
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Digit and radix utilities for [`NumExt`][] types.
+//!
+//! [`NumExt`]: super::NumExt
+
+use std::iter::FusedIterator;
+
+/// An iterator over the digits of an integer's magnitude in some radix, created
+/// by [`NumExt::digits`]. Digits are yielded most significant first; reverse the
+/// iterator for least significant first.
+///
+/// [`NumExt::digits`]: crate::NumExt::digits
+#[derive(Clone, Debug)]
+pub struct Digits {
+	/// The remaining digits.
+	value: u128,
+	/// The place value of the most significant remaining digit.
+	place: u128,
+	radix: u128,
+	len: u32
+}
+
+impl Digits {
+	pub(crate) fn new(value: u128, radix: u32) -> Self {
+		let len = digit_count(value, radix);
+		let radix = radix as u128;
+		Self { value, place: radix.pow(len - 1), radix, len }
+	}
+}
+
+impl Iterator for Digits {
+	type Item = u32;
+
+	fn next(&mut self) -> Option<u32> {
+		if self.len == 0 {
+			return None
+		}
+
+		let digit = self.value / self.place;
+		self.value %= self.place;
+		self.place /= self.radix;
+		self.len -= 1;
+		Some(digit as u32)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.len as usize, Some(self.len as usize))
+	}
+}
+
+impl DoubleEndedIterator for Digits {
+	fn next_back(&mut self) -> Option<u32> {
+		if self.len == 0 {
+			return None
+		}
+
+		let digit = self.value % self.radix;
+		self.value /= self.radix;
+		self.place /= self.radix;
+		self.len -= 1;
+		Some(digit as u32)
+	}
+}
+
+impl ExactSizeIterator for Digits { }
+
+impl FusedIterator for Digits { }
+
+/// Returns the number of digits in `value`. Zero has one digit.
+pub fn digit_count(value: u128, radix: u32) -> u32 {
+	check_radix(radix);
+	value.checked_ilog(radix as u128).map_or(1, |log| log + 1)
+}
+
+/// Returns the sum of the digits in `value`.
+pub fn digit_sum(value: u128, radix: u32) -> u128 {
+	Digits::new(value, radix).map(u128::from).sum()
+}
+
+/// Reverses the digits of `value`, or returns `None` on overflow.
+pub fn reverse_digits(value: u128, radix: u32) -> Option<u128> {
+	from_digits(Digits::new(value, radix).rev(), radix)
+}
+
+/// Builds a value from digits, most significant first, or returns `None` if a
+/// digit is out of range for the radix or the value overflows.
+pub fn from_digits(digits: impl IntoIterator<Item = u32>, radix: u32) -> Option<u128> {
+	check_radix(radix);
+	digits.into_iter().try_fold(0u128, |value, digit| {
+		(digit < radix).then_some(())?;
+		value.checked_mul(radix as u128)?.checked_add(digit as u128)
+	})
+}
+
+fn check_radix(radix: u32) {
+	assert!(radix >= 2, "radix must be at least 2, got {radix}");
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::NumExt;
+
+	#[test]
+	fn digits_from_both_ends() {
+		let mut digits = 123_456u32.digits(10);
+		assert_eq!(digits.len(), 6);
+		assert_eq!(digits.next(), Some(1));
+		assert_eq!(digits.next_back(), Some(6));
+		assert_eq!(digits.next_back(), Some(5));
+		assert_eq!(digits.next(), Some(2));
+		assert_eq!(digits.len(), 2);
+		assert_eq!(digits.next_back(), Some(4));
+		assert_eq!(digits.next(), Some(3));
+		assert_eq!(digits.next(), None);
+		assert_eq!(digits.next_back(), None);
+
+		assert_eq!(0u8.digits(10).collect::<Vec<_>>(), [0]);
+		assert_eq!((-255i16).digits(16).rev().collect::<Vec<_>>(), [15, 15]);
+		assert_eq!(i8::MIN.digits(2).collect::<Vec<_>>(), [1, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(u128::MAX.digits(2).len(), 128);
+		assert!(u128::MAX.digits(2).rev().all(|digit| digit == 1));
+		assert_eq!(u128::MAX.digits(u32::MAX).count() as u32, u128::MAX.digit_count(u32::MAX));
+	}
+
+	#[test]
+	fn digit_count_and_sum() {
+		assert_eq!(0u8.digit_count(10), 1);
+		assert_eq!(9u8.digit_count(10), 1);
+		assert_eq!(10u8.digit_count(10), 2);
+		assert_eq!(i64::MIN.digit_count(10), 19);
+		assert_eq!(u128::MAX.digit_count(10), 39);
+		assert_eq!(u128::MAX.digit_count(16), 32);
+		assert_eq!((-1234i32).digit_sum(10), 10);
+		assert_eq!(u8::MAX.digit_sum(2), 8);
+		assert_eq!(u128::MAX.digit_sum(2), 128);
+	}
+
+	#[test]
+	fn reverse_digits() {
+		assert_eq!(1200u32.reverse_digits(10), Some(21));
+		assert_eq!((-123i16).reverse_digits(10), Some(-321));
+		assert_eq!(0u8.reverse_digits(10), Some(0));
+		assert_eq!(0b1011u8.reverse_digits(2), Some(0b1101));
+		assert_eq!(199u8.reverse_digits(10), None);
+		assert_eq!(i8::MIN.reverse_digits(10), None);
+		assert_eq!(u32::MAX.reverse_digits(10), None);
+		assert_eq!(i32::MIN.reverse_digits(10), None);
+		assert_eq!(1_000_000_009u32.reverse_digits(10), None);
+		assert_eq!(1_000_000_003u32.reverse_digits(10), Some(3_000_000_001));
+		assert_eq!((-1_000_000_002i32).reverse_digits(10), Some(-2_000_000_001));
+	}
+
+	#[test]
+	fn from_digits() {
+		assert_eq!(u32::from_digits([1, 2, 3], 10), Some(123));
+		assert_eq!(u8::from_digits([], 10), Some(0));
+		assert_eq!(u8::from_digits([0xF, 0xF], 16), Some(255));
+		assert_eq!(u8::from_digits([1, 0, 0, 0, 0, 0, 0, 0, 0], 2), None);
+		assert_eq!(u8::from_digits([2, 5, 6], 10), None);
+		assert_eq!(i8::from_digits([1, 2, 8], 10), None);
+		assert_eq!(u32::from_digits([1, 10], 10), None);
+		assert_eq!(u32::from_digits([2], 2), None);
+		assert_eq!(u32::from_digits([u32::MAX - 1], u32::MAX), Some(u32::MAX - 1));
+		assert_eq!(u128::from_digits(u128::MAX.digits(7), 7), Some(u128::MAX));
+		assert_eq!(u128::from_digits([1; 129], 2), None);
+	}
+
+	#[test]
+	#[should_panic = "radix must be at least 2"]
+	fn radix_one() {
+		5u8.digit_count(1);
+	}
+}
@@ -0,0 +1,429 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human-readable [`Display`] adapters for [`NumExt`][] types. Nothing is
+//! allocated; each adapter writes straight into the formatter. Adapters honour
+//! the formatter's width, fill and alignment, left-aligning by default like
+//! strings do.
+//!
+//! [`NumExt`]: super::NumExt
+
+use std::fmt::{self, Alignment, Display, Formatter, Write};
+use super::Digits;
+
+/// Splits sign-extended bits into a sign and magnitude.
+fn sign_magnitude((negative, bits): (bool, u128)) -> (bool, u128) {
+	(negative, if negative { bits.wrapping_neg() } else { bits })
+}
+
+fn write_sign(w: &mut dyn Write, negative: bool) -> fmt::Result {
+	if negative { w.write_char('-') } else { Ok(()) }
+}
+
+/// Counts the characters written to it.
+struct CharCount(usize);
+
+impl Write for CharCount {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		self.0 += s.chars().count();
+		Ok(())
+	}
+}
+
+/// Writes the output of `write` padded to the formatter's width, as
+/// [`Formatter::pad`] does for strings. Rather than buffering, the output is
+/// written twice: once to measure it, then for real.
+fn pad(f: &mut Formatter<'_>, write: impl Fn(&mut dyn Write) -> fmt::Result) -> fmt::Result {
+	let Some(width) = f.width() else { return write(f) };
+	let mut count = CharCount(0);
+	write(&mut count)?;
+
+	let padding = width.saturating_sub(count.0);
+	let (before, after) = match f.align() {
+		Some(Alignment::Right) => (padding, 0),
+		Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+		Some(Alignment::Left) | None => (0, padding)
+	};
+	let fill = f.fill();
+	for _ in 0..before {
+		f.write_char(fill)?;
+	}
+	write(f)?;
+	for _ in 0..after {
+		f.write_char(fill)?;
+	}
+	Ok(())
+}
+
+/// Displays a byte count scaled to the largest fitting SI (`kB`, `MB`, ...) or
+/// IEC (`KiB`, `MiB`, ...) unit, created by [`NumExt::as_bytes_si`] and
+/// [`NumExt::as_bytes_iec`]. Scaled values are shown with one decimal place
+/// unless the formatter specifies a precision. A value that would round up to a
+/// whole step, such as `1000.0 kB`, is shown in the next unit instead.
+///
+/// [`NumExt::as_bytes_si`]: crate::NumExt::as_bytes_si
+/// [`NumExt::as_bytes_iec`]: crate::NumExt::as_bytes_iec
+#[derive(Copy, Clone, Debug)]
+pub struct ByteSize {
+	negative: bool,
+	bytes: u128,
+	iec: bool
+}
+
+impl ByteSize {
+	const SI_UNITS: &'static [&'static str] = &["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB", "RB", "QB"];
+	const IEC_UNITS: &'static [&'static str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", "ZiB", "YiB"];
+
+	pub(crate) fn new(bits: (bool, u128), iec: bool) -> Self {
+		let (negative, bytes) = sign_magnitude(bits);
+		Self { negative, bytes, iec }
+	}
+
+	fn write(&self, w: &mut dyn Write, precision: usize) -> fmt::Result {
+		let (step, units) = if self.iec {
+			(1024, Self::IEC_UNITS)
+		} else {
+			(1000, Self::SI_UNITS)
+		};
+
+		write_sign(w, self.negative)?;
+		if self.bytes < step {
+			return write!(w, "{} B", self.bytes)
+		}
+
+		let scale = |exp: usize| self.bytes as f64 / (step as f64).powi(exp as i32);
+		let mut exp = (self.bytes.ilog(step) as usize).min(units.len() - 1);
+		// Values within half the last shown digit of a step round up to it.
+		let round_up = 0.5 / 10f64.powi(precision.min(i32::MAX as usize) as i32);
+		if exp < units.len() - 1 && scale(exp) >= step as f64 - round_up {
+			exp += 1;
+		}
+		write!(w, "{:.precision$} {}", scale(exp), units[exp])
+	}
+}
+
+impl Display for ByteSize {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let precision = f.precision().unwrap_or(1);
+		pad(f, |w| self.write(w, precision))
+	}
+}
+
+/// Displays an integer with its digits grouped in threes by a separator, such as
+/// `1,234,567`, created by [`NumExt::with_separators`].
+///
+/// [`NumExt::with_separators`]: crate::NumExt::with_separators
+#[derive(Copy, Clone, Debug)]
+pub struct Separated {
+	negative: bool,
+	magnitude: u128,
+	separator: char
+}
+
+impl Separated {
+	pub(crate) fn new(bits: (bool, u128), separator: char) -> Self {
+		let (negative, magnitude) = sign_magnitude(bits);
+		Self { negative, magnitude, separator }
+	}
+
+	fn write(&self, w: &mut dyn Write) -> fmt::Result {
+		write_sign(w, self.negative)?;
+		let digits = Digits::new(self.magnitude, 10);
+		let len = digits.len();
+		for (i, digit) in digits.enumerate() {
+			if i > 0 && (len - i).is_multiple_of(3) {
+				w.write_char(self.separator)?;
+			}
+			write!(w, "{digit}")?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for Separated {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		pad(f, |w| self.write(w))
+	}
+}
+
+/// Displays an integer as an English ordinal, such as `21st`, created by
+/// [`NumExt::ordinal`].
+///
+/// [`NumExt::ordinal`]: crate::NumExt::ordinal
+#[derive(Copy, Clone, Debug)]
+pub struct Ordinal {
+	negative: bool,
+	magnitude: u128
+}
+
+impl Ordinal {
+	pub(crate) fn new(bits: (bool, u128)) -> Self {
+		let (negative, magnitude) = sign_magnitude(bits);
+		Self { negative, magnitude }
+	}
+
+	fn write(&self, w: &mut dyn Write) -> fmt::Result {
+		let suffix = match (self.magnitude % 100, self.magnitude % 10) {
+			(11..=13, _) => "th",
+			(_, 1) => "st",
+			(_, 2) => "nd",
+			(_, 3) => "rd",
+			_ => "th"
+		};
+		write_sign(w, self.negative)?;
+		write!(w, "{}{suffix}", self.magnitude)
+	}
+}
+
+impl Display for Ordinal {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		pad(f, |w| self.write(w))
+	}
+}
+
+/// Displays an integer from 1 to 3999 as a Roman numeral, such as `MMXXIV`,
+/// created by [`NumExt::roman`].
+///
+/// [`NumExt::roman`]: crate::NumExt::roman
+#[derive(Copy, Clone, Debug)]
+pub struct Roman(u16);
+
+impl Roman {
+	/// The largest number expressible in standard Roman numerals.
+	pub const MAX: u16 = 3999;
+
+	const NUMERALS: [(u16, &'static str); 13] = [
+		(1000, "M"), (900, "CM"), (500, "D"), (400, "CD"),
+		(100, "C"), (90, "XC"), (50, "L"), (40, "XL"),
+		(10, "X"), (9, "IX"), (5, "V"), (4, "IV"),
+		(1, "I"),
+	];
+
+	pub(crate) fn new((negative, bits): (bool, u128)) -> Option<Self> {
+		if negative || !(1..=Self::MAX as u128).contains(&bits) {
+			return None
+		}
+
+		Some(Self(bits as u16))
+	}
+
+	fn write(&self, w: &mut dyn Write) -> fmt::Result {
+		let mut value = self.0;
+		for (step, numeral) in Self::NUMERALS {
+			while value >= step {
+				w.write_str(numeral)?;
+				value -= step;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Display for Roman {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		pad(f, |w| self.write(w))
+	}
+}
+
+/// Displays an integer in English words, such as `negative forty-two`, created by
+/// [`NumExt::to_words`].
+///
+/// [`NumExt::to_words`]: crate::NumExt::to_words
+#[derive(Copy, Clone, Debug)]
+pub struct Words {
+	negative: bool,
+	magnitude: u128
+}
+
+impl Words {
+	const ONES: [&'static str; 20] = [
+		"zero", "one", "two", "three", "four", "five", "six", "seven", "eight",
+		"nine", "ten", "eleven", "twelve", "thirteen", "fourteen", "fifteen",
+		"sixteen", "seventeen", "eighteen", "nineteen",
+	];
+	const TENS: [&'static str; 10] = [
+		"", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty",
+		"ninety",
+	];
+	/// Short-scale names for powers of one thousand, enough for `u128::MAX`.
+	const SCALES: [&'static str; 13] = [
+		"", "thousand", "million", "billion", "trillion", "quadrillion",
+		"quintillion", "sextillion", "septillion", "octillion", "nonillion",
+		"decillion", "undecillion",
+	];
+
+	pub(crate) fn new(bits: (bool, u128)) -> Self {
+		let (negative, magnitude) = sign_magnitude(bits);
+		Self { negative, magnitude }
+	}
+
+	/// Writes a number below one thousand.
+	fn write_group(w: &mut dyn Write, group: u128) -> fmt::Result {
+		let (hundreds, rest) = (group / 100, group % 100);
+		if hundreds > 0 {
+			write!(w, "{} hundred", Self::ONES[hundreds as usize])?;
+			if rest > 0 {
+				w.write_char(' ')?;
+			}
+		}
+
+		match rest {
+			0 => Ok(()),
+			1..20 => w.write_str(Self::ONES[rest as usize]),
+			_ => {
+				w.write_str(Self::TENS[rest as usize / 10])?;
+				match rest % 10 {
+					0 => Ok(()),
+					ones => write!(w, "-{}", Self::ONES[ones as usize])
+				}
+			}
+		}
+	}
+
+	fn write(&self, w: &mut dyn Write) -> fmt::Result {
+		if self.magnitude == 0 {
+			return w.write_str(Self::ONES[0])
+		}
+
+		if self.negative {
+			w.write_str("negative ")?;
+		}
+
+		let mut first = true;
+		for scale in (0..Self::SCALES.len()).rev() {
+			let group = self.magnitude / 1000u128.pow(scale as u32) % 1000;
+			if group == 0 {
+				continue
+			}
+
+			if !first {
+				w.write_char(' ')?;
+			}
+			first = false;
+
+			Self::write_group(w, group)?;
+			if scale > 0 {
+				write!(w, " {}", Self::SCALES[scale])?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Display for Words {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		pad(f, |w| self.write(w))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::NumExt;
+
+	#[test]
+	fn byte_size() {
+		assert_eq!(0u8.as_bytes_si().to_string(), "0 B");
+		assert_eq!(999u16.as_bytes_si().to_string(), "999 B");
+		assert_eq!(1000u16.as_bytes_si().to_string(), "1.0 kB");
+		assert_eq!(1023u16.as_bytes_iec().to_string(), "1023 B");
+		assert_eq!(1536u16.as_bytes_iec().to_string(), "1.5 KiB");
+		assert_eq!((-1_500_000i32).as_bytes_si().to_string(), "-1.5 MB");
+		assert_eq!(format!("{:.3}", 1_234_567u32.as_bytes_si()), "1.235 MB");
+		assert_eq!(format!("{:.0}", 1_500u32.as_bytes_si()), "2 kB");
+		assert_eq!(u128::MAX.as_bytes_si().to_string(), "340282366.9 QB");
+		assert_eq!(u128::MAX.as_bytes_iec().to_string(), "281474976710656.0 YiB");
+	}
+
+	#[test]
+	fn byte_size_rounds_into_next_unit() {
+		assert_eq!(999_949u32.as_bytes_si().to_string(), "999.9 kB");
+		assert_eq!(999_950u32.as_bytes_si().to_string(), "1.0 MB");
+		assert_eq!(format!("{:.2}", 999_995u32.as_bytes_si()), "1.00 MB");
+		assert_eq!(format!("{:.0}", 999_500u32.as_bytes_si()), "1 MB");
+		assert_eq!(1_048_575u32.as_bytes_iec().to_string(), "1.0 MiB");
+		assert_eq!(format!("{:.3}", 1_048_575u32.as_bytes_iec()), "1023.999 KiB");
+	}
+
+	#[test]
+	fn separators() {
+		assert_eq!(0u8.with_separators(',').to_string(), "0");
+		assert_eq!(999u16.with_separators(',').to_string(), "999");
+		assert_eq!(1000u16.with_separators(',').to_string(), "1,000");
+		assert_eq!(1_234_567u32.with_separators('_').to_string(), "1_234_567");
+		assert_eq!((-1234i16).with_separators('.').to_string(), "-1.234");
+		assert_eq!(i8::MIN.with_separators(',').to_string(), "-128");
+		assert_eq!(i64::MIN.with_separators(',').to_string(), "-9,223,372,036,854,775,808");
+		assert_eq!(
+			u128::MAX.with_separators(' ').to_string(),
+			"340 282 366 920 938 463 463 374 607 431 768 211 455"
+		);
+	}
+
+	#[test]
+	fn ordinals() {
+		let ordinal = |n: i32| n.ordinal().to_string();
+		assert_eq!(ordinal(0), "0th");
+		assert_eq!(ordinal(1), "1st");
+		assert_eq!(ordinal(2), "2nd");
+		assert_eq!(ordinal(3), "3rd");
+		assert_eq!(ordinal(4), "4th");
+		assert_eq!(ordinal(11), "11th");
+		assert_eq!(ordinal(12), "12th");
+		assert_eq!(ordinal(13), "13th");
+		assert_eq!(ordinal(21), "21st");
+		assert_eq!(ordinal(102), "102nd");
+		assert_eq!(ordinal(111), "111th");
+		assert_eq!(ordinal(112), "112th");
+		assert_eq!(ordinal(-23), "-23rd");
+		assert_eq!(u128::MAX.ordinal().to_string(), format!("{}th", u128::MAX));
+	}
+
+	#[test]
+	fn roman() {
+		let roman = |n: i32| n.roman().map(|roman| roman.to_string());
+		assert_eq!(roman(1).as_deref(), Some("I"));
+		assert_eq!(roman(4).as_deref(), Some("IV"));
+		assert_eq!(roman(9).as_deref(), Some("IX"));
+		assert_eq!(roman(14).as_deref(), Some("XIV"));
+		assert_eq!(roman(1994).as_deref(), Some("MCMXCIV"));
+		assert_eq!(roman(2024).as_deref(), Some("MMXXIV"));
+		assert_eq!(roman(3999).as_deref(), Some("MMMCMXCIX"));
+		assert_eq!(roman(0), None);
+		assert_eq!(roman(4000), None);
+		assert_eq!(roman(-1), None);
+		assert!(u128::MAX.roman().is_none());
+	}
+
+	#[test]
+	fn words() {
+		let words = |n: i64| n.to_words().to_string();
+		assert_eq!(words(0), "zero");
+		assert_eq!(words(7), "seven");
+		assert_eq!(words(19), "nineteen");
+		assert_eq!(words(20), "twenty");
+		assert_eq!(words(-42), "negative forty-two");
+		assert_eq!(words(100), "one hundred");
+		assert_eq!(words(110), "one hundred ten");
+		assert_eq!(words(1_000_001), "one million one");
+		assert_eq!(words(12_345), "twelve thousand three hundred forty-five");
+		assert_eq!(
+			i8::MIN.to_words().to_string(),
+			"negative one hundred twenty-eight"
+		);
+		assert!(u128::MAX.to_words().to_string().starts_with("three hundred forty undecillion"));
+		assert!(u128::MAX.to_words().to_string().ends_with("four hundred fifty-five"));
+	}
+
+	#[test]
+	fn padding() {
+		assert_eq!(format!("[{:>10}]", 42u8.with_separators(',')), "[        42]");
+		assert_eq!(format!("[{:10}]", 1234u16.with_separators(',')), "[1,234     ]");
+		assert_eq!(format!("[{:*^9}]", (-1234i16).with_separators(',')), "[*-1,234**]");
+		assert_eq!(format!("[{:>9}]", 1536u16.as_bytes_iec()), "[  1.5 KiB]");
+		assert_eq!(format!("[{:>9.2}]", 1536u16.as_bytes_iec()), "[ 1.50 KiB]");
+		assert_eq!(format!("[{:<6}]", 21u8.ordinal()), "[21st  ]");
+		assert_eq!(format!("[{:>6}]", 14u8.roman().unwrap()), "[   XIV]");
+		assert_eq!(format!("[{:-^7}]", 3u8.to_words()), "[-three-]");
+		assert_eq!(format!("[{:2}]", 1234u16.with_separators(',')), "[1,234]");
+		assert_eq!(format!("[{:>5}]", 1234u16.with_separators('·')), "[1·234]");
+	}
+}
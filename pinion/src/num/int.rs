@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integer bound traits for generic code over [`NumExt`] types.
+
+use std::fmt::{Binary, Debug, Display, LowerHex, Octal, UpperHex};
+use std::hash::Hash;
+use std::iter::{Product, Sum};
+use std::ops::{
+	Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign,
+	Div, DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr,
+	ShrAssign, Sub, SubAssign,
+};
+use std::str::FromStr;
+use super::{NumExt, SNumExt};
+
+/// An integer of any primitive type, with its constants and arithmetic available
+/// to generic code. Implemented for the same types as [`NumExt`]:
+///
+/// ```
+/// # use pinion_rs::{Int, NumExt};
+/// /// Averages two integers without overflowing, rounding down.
+/// fn midpoint<T: Int>(a: T, b: T) -> T {
+/// 	(a >> 1) + (b >> 1) + (a & b & T::ONE)
+/// }
+///
+/// assert_eq!(midpoint(u8::MAX, u8::MAX - 2), 254);
+/// assert_eq!(midpoint(i64::MIN, i64::MIN + 2), i64::MIN + 1);
+/// assert_eq!(midpoint(-3i8, 0).positive(), None);
+/// ```
+pub trait Int:
+	NumExt + Copy + Default + Eq + Ord + Hash + Debug + Display +
+	Binary + Octal + LowerHex + UpperHex + FromStr + Sum + Product +
+	Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> +
+	Div<Output = Self> + Rem<Output = Self> +
+	AddAssign + SubAssign + MulAssign + DivAssign + RemAssign +
+	Not<Output = Self> + BitAnd<Output = Self> + BitOr<Output = Self> +
+	BitXor<Output = Self> + Shl<u32, Output = Self> + Shr<u32, Output = Self> +
+	BitAndAssign + BitOrAssign + BitXorAssign + ShlAssign<u32> + ShrAssign<u32> {
+	/// Zero.
+	const ZERO: Self;
+	/// One.
+	const ONE: Self;
+	/// The smallest value of this type.
+	const MIN: Self;
+	/// The largest value of this type.
+	const MAX: Self;
+	/// The width of this type in bits.
+	const BITS: u32;
+}
+
+/// A signed integer of any primitive type.
+///
+/// ```
+/// # use pinion_rs::Signed;
+/// fn distance_from_zero<T: Signed>(n: T) -> Option<T> {
+/// 	if n == T::MIN { None } else if n < T::ZERO { Some(-n) } else { Some(n) }
+/// }
+///
+/// assert_eq!(distance_from_zero(-5i32), Some(5));
+/// assert_eq!(distance_from_zero(i8::MIN), None);
+/// ```
+pub trait Signed: Int + SNumExt + Neg<Output = Self> { }
+
+/// An unsigned integer of any primitive type.
+///
+/// ```
+/// # use pinion_rs::Unsigned;
+/// fn fill_low_bits<T: Unsigned>(count: u32) -> T {
+/// 	if count >= T::BITS { T::MAX } else { (T::ONE << count) - T::ONE }
+/// }
+///
+/// assert_eq!(fill_low_bits::<u8>(3), 0b111);
+/// assert_eq!(fill_low_bits::<u128>(128), u128::MAX);
+/// ```
+pub trait Unsigned: Int { }
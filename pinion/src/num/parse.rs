@@ -0,0 +1,493 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing of human-written integers for [`NumExt`][] types.
+//!
+//! [`NumExt`]: super::NumExt
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use super::arith::gcd;
+
+/// An error returned by [`NumExt::parse_human`], with the byte position in the
+/// input at which it was found.
+///
+/// [`NumExt::parse_human`]: crate::NumExt::parse_human
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParseHumanError {
+	kind: ParseHumanErrorKind,
+	position: usize
+}
+
+/// The kind of [`ParseHumanError`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseHumanErrorKind {
+	/// The input contains no number.
+	Empty,
+	/// A character isn't a valid digit where one was expected.
+	InvalidDigit,
+	/// The unit suffix isn't recognized.
+	InvalidSuffix,
+	/// The number has a fractional part after scaling, such as `1.5` or `1.0005k`.
+	Fractional,
+	/// The number doesn't fit in the target type.
+	Overflow,
+}
+
+impl ParseHumanError {
+	fn new(kind: ParseHumanErrorKind, position: usize) -> Self {
+		Self { kind, position }
+	}
+
+	/// Returns the kind of error.
+	pub fn kind(&self) -> ParseHumanErrorKind { self.kind }
+
+	/// Returns the byte position in the input at which the error was found.
+	pub fn position(&self) -> usize { self.position }
+}
+
+impl Display for ParseHumanError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let message = match self.kind {
+			ParseHumanErrorKind::Empty => "no number to parse",
+			ParseHumanErrorKind::InvalidDigit => "invalid digit",
+			ParseHumanErrorKind::InvalidSuffix => "unrecognized unit suffix",
+			ParseHumanErrorKind::Fractional => "number is not an integer",
+			ParseHumanErrorKind::Overflow => "number is out of range for the type"
+		};
+		write!(f, "{message} at position {}", self.position)
+	}
+}
+
+impl Error for ParseHumanError { }
+
+use ParseHumanErrorKind::*;
+
+/// A cursor over the input bytes, tracking the position for errors.
+struct Cursor<'a> {
+	input: &'a [u8],
+	position: usize
+}
+
+impl Cursor<'_> {
+	fn peek(&self) -> Option<u8> { self.input.get(self.position).copied() }
+
+	fn peek_at(&self, offset: usize) -> Option<u8> {
+		self.input.get(self.position + offset).copied()
+	}
+
+	fn eat(&mut self, byte: u8) -> bool {
+		let matched = self.peek().is_some_and(|b| b.eq_ignore_ascii_case(&byte));
+		if matched {
+			self.position += 1;
+		}
+		matched
+	}
+
+	fn skip_whitespace(&mut self) {
+		while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+			self.position += 1;
+		}
+	}
+
+	fn error(&self, kind: ParseHumanErrorKind) -> ParseHumanError {
+		ParseHumanError::new(kind, self.position)
+	}
+
+	/// Reads digits in `radix` with `_` separators, accumulating them into `value`.
+	/// Trailing zeros aren't accumulated but counted, so a fraction's insignificant
+	/// zeros can't overflow. Returns the number of digits and of trailing zeros.
+	fn digits(&mut self, radix: u32, value: &mut u128) -> Result<(u32, u32), ParseHumanError> {
+		let (mut count, mut zeros) = (0, 0);
+		while let Some(byte) = self.peek() {
+			if byte == b'_' {
+				// Separators only count between two digits; any other `_` stops here
+				// and is rejected by the caller.
+				if count == 0 || !self.peek_at(1).is_some_and(|b| (b as char).is_digit(radix)) {
+					break
+				}
+				self.position += 1;
+				continue
+			}
+
+			let Some(digit) = (byte as char).to_digit(radix) else { break };
+			if digit == 0 {
+				zeros += 1;
+			} else {
+				*value = self.shift(*value, radix, zeros + 1)?
+					.checked_add(digit as u128)
+					.ok_or(self.error(Overflow))?;
+				zeros = 0;
+			}
+			count += 1;
+			self.position += 1;
+		}
+		Ok((count, zeros))
+	}
+
+	/// Reads decimal exponent digits with `_` separators. Exponents too large for an
+	/// `i64` saturate, since the scale they feed saturates anyway; whether that
+	/// ends up zero, a fraction or an overflow depends on the mantissa and sign.
+	fn exponent(&mut self) -> i64 {
+		let mut exponent = 0i64;
+		let mut count = 0;
+		while let Some(byte) = self.peek() {
+			if byte == b'_' {
+				if count == 0 || !self.peek_at(1).is_some_and(|b| b.is_ascii_digit()) {
+					break
+				}
+				self.position += 1;
+				continue
+			}
+
+			let Some(digit) = (byte as char).to_digit(10) else { break };
+			exponent = exponent.saturating_mul(10).saturating_add(digit as i64);
+			count += 1;
+			self.position += 1;
+		}
+		exponent
+	}
+
+	/// Shifts `value` left by `places` digits in `radix`.
+	fn shift(&self, value: u128, radix: u32, places: u32) -> Result<u128, ParseHumanError> {
+		if value == 0 {
+			return Ok(0)
+		}
+
+		(radix as u128)
+			.checked_pow(places)
+			.and_then(|factor| value.checked_mul(factor))
+			.ok_or(self.error(Overflow))
+	}
+}
+
+/// The parts of a number which can push it out of range, for locating overflow.
+struct Number {
+	negative: bool,
+	radix: u32,
+	/// The position of the first integer digit.
+	start: usize,
+	/// The position of the exponent's `e`, if any.
+	exponent: Option<usize>,
+	/// The position of the unit multiplier, if any.
+	suffix: Option<usize>
+}
+
+impl Number {
+	/// Creates an overflow error at the part of the number which makes it too large
+	/// to fit: the integer digit at which it stops fitting, else the exponent, else
+	/// the unit suffix. Failing all those, the error is at `fallback`.
+	fn overflow(&self, input: &[u8], fits: impl Fn(bool, u128) -> bool, fallback: usize) -> ParseHumanError {
+		let mut value = 0u128;
+		for (position, &byte) in input.iter().enumerate().skip(self.start) {
+			if byte == b'_' {
+				continue
+			}
+
+			let Some(digit) = (byte as char).to_digit(self.radix) else { break };
+			match value.checked_mul(self.radix as u128).and_then(|value| value.checked_add(digit as u128)) {
+				Some(next) if fits(self.negative, next) => value = next,
+				_ => return ParseHumanError::new(Overflow, position)
+			}
+		}
+
+		ParseHumanError::new(Overflow, self.exponent.or(self.suffix).unwrap_or(fallback))
+	}
+}
+
+/// Parses a human-written integer into its sign and magnitude, where `fits`
+/// checks whether a magnitude is in range for the target type.
+pub fn parse(input: &str, fits: impl Fn(bool, u128) -> bool) -> Result<(bool, u128), ParseHumanError> {
+	let mut cursor = Cursor { input: input.as_bytes(), position: 0 };
+	cursor.skip_whitespace();
+	let start = cursor.position;
+
+	let negative = match cursor.peek() {
+		Some(b'-') => { cursor.position += 1; true }
+		Some(b'+') => { cursor.position += 1; false }
+		_ => false
+	};
+
+	let radix = match (cursor.peek(), cursor.peek_at(1).map(|b| b.to_ascii_lowercase())) {
+		(Some(b'0'), Some(b'x')) => 16,
+		(Some(b'0'), Some(b'o')) => 8,
+		(Some(b'0'), Some(b'b')) => 2,
+		_ => 10
+	};
+	if radix != 10 {
+		cursor.position += 2;
+	}
+
+	let mut number = Number { negative, radix, start: cursor.position, exponent: None, suffix: None };
+	let parts = if radix == 10 {
+		parse_decimal(&mut cursor, &mut number)
+	} else {
+		let mut value = 0;
+		cursor.digits(radix, &mut value).and_then(|(count, zeros)| {
+			if count == 0 {
+				return Err(cursor.error(InvalidDigit))
+			}
+			Ok((cursor.shift(value, radix, zeros)?, 0, 1))
+		})
+	};
+	let (mantissa, scale, multiplier) = parts.map_err(|error| match error.kind {
+		Overflow => number.overflow(cursor.input, &fits, error.position),
+		_ => error
+	})?;
+
+	cursor.skip_whitespace();
+	match cursor.peek() {
+		None => { }
+		Some(byte) if byte.is_ascii_digit() || byte == b'.' || byte == b'_' => return Err(cursor.error(InvalidDigit)),
+		Some(_) => return Err(cursor.error(InvalidSuffix))
+	}
+
+	match scale_exact(mantissa, scale, multiplier) {
+		Ok(magnitude) if fits(negative, magnitude) => Ok((negative && magnitude != 0, magnitude)),
+		Ok(_) | Err(Overflow) => Err(number.overflow(cursor.input, &fits, start)),
+		Err(kind) => Err(ParseHumanError::new(kind, start))
+	}
+}
+
+/// Parses a decimal number with an optional fraction, exponent and unit suffix,
+/// into a mantissa, a power of ten dividing it, and the unit multiplier. A unit
+/// multiplier can't follow an exponent.
+fn parse_decimal(cursor: &mut Cursor<'_>, number: &mut Number) -> Result<(u128, i64, u128), ParseHumanError> {
+	let mut mantissa = 0u128;
+	let (integer_digits, zeros) = cursor.digits(10, &mut mantissa)?;
+	// Trailing zeros of the integer part are significant.
+	mantissa = cursor.shift(mantissa, 10, zeros)?;
+
+	let mut scale = 0i64;
+	let mut fraction_digits = 0;
+	if cursor.peek() == Some(b'.') {
+		cursor.position += 1;
+		let zeros;
+		(fraction_digits, zeros) = cursor.digits(10, &mut mantissa)?;
+		// Trailing zeros of the fraction are insignificant.
+		scale = (fraction_digits - zeros) as i64;
+	}
+
+	if integer_digits == 0 && fraction_digits == 0 {
+		return Err(cursor.error(if cursor.peek().is_none() { Empty } else { InvalidDigit }))
+	}
+
+	// An exponent only follows if digits do; a lone `e` is rejected as a suffix.
+	let exponent_follows = match (cursor.peek_at(1), cursor.peek_at(2)) {
+		(Some(b'+' | b'-'), Some(digit)) | (Some(digit), _) => digit.is_ascii_digit(),
+		_ => false
+	};
+	if cursor.peek().is_some_and(|b| b.eq_ignore_ascii_case(&b'e')) && exponent_follows {
+		number.exponent = Some(cursor.position);
+		cursor.position += 1;
+		let exponent_negative = match cursor.peek() {
+			Some(b'-') => { cursor.position += 1; true }
+			Some(b'+') => { cursor.position += 1; false }
+			_ => false
+		};
+		let exponent = cursor.exponent();
+		scale = if exponent_negative {
+			scale.saturating_add(exponent)
+		} else {
+			scale.saturating_sub(exponent)
+		};
+	}
+
+	cursor.skip_whitespace();
+	let suffix_start = cursor.position;
+	let multiplier = parse_suffix(cursor)
+		.filter(|&multiplier| multiplier == 1 || number.exponent.is_none())
+		.ok_or(ParseHumanError::new(InvalidSuffix, suffix_start))?;
+	if multiplier > 1 {
+		number.suffix = Some(suffix_start);
+	}
+	Ok((mantissa, scale, multiplier))
+}
+
+/// Computes `mantissa * multiplier / 10^scale` exactly.
+fn scale_exact(mantissa: u128, scale: i64, multiplier: u128) -> Result<u128, ParseHumanErrorKind> {
+	if mantissa == 0 {
+		return Ok(0)
+	}
+
+	if scale <= 0 {
+		let power = 10u128.checked_pow(scale.unsigned_abs().try_into().map_err(|_| Overflow)?).ok_or(Overflow)?;
+		return mantissa
+			.checked_mul(power)
+			.and_then(|value| value.checked_mul(multiplier))
+			.ok_or(Overflow)
+	}
+
+	// Any divisor wider than u128 leaves a fraction of a non-zero mantissa.
+	let divisor = u32::try_from(scale).ok().and_then(|scale| 10u128.checked_pow(scale)).ok_or(Fractional)?;
+	let common = gcd(multiplier, divisor);
+	let (multiplier, divisor) = (multiplier / common, divisor / common);
+	if !mantissa.is_multiple_of(divisor) {
+		return Err(Fractional)
+	}
+	(mantissa / divisor).checked_mul(multiplier).ok_or(Overflow)
+}
+
+/// Parses an optional SI (`k`, `M`, ...) or IEC (`Ki`, `Mi`, ...) multiplier
+/// followed by an optional `B`, or returns `None` if it isn't recognized. Unit
+/// letters are case-insensitive. Since a lone `e` reads as an exponent, exa must
+/// be written `EB`, `Ei` or `EiB`.
+fn parse_suffix(cursor: &mut Cursor<'_>) -> Option<u128> {
+	const PREFIXES: &[u8] = b"kmgtpezyrq";
+
+	let mut multiplier = 1;
+	if let Some(index) = cursor.peek().and_then(|b| PREFIXES.iter().position(|&p| p.eq_ignore_ascii_case(&b))) {
+		let unit_follows = cursor.peek_at(1).is_some_and(|b| matches!(b.to_ascii_lowercase(), b'i' | b'b'));
+		if PREFIXES[index] == b'e' && !unit_follows {
+			return None
+		}
+
+		cursor.position += 1;
+		let power = index as u32 + 1;
+		multiplier = if cursor.eat(b'i') {
+			1024u128.checked_pow(power)?
+		} else {
+			10u128.checked_pow(3 * power)?
+		};
+	}
+
+	cursor.eat(b'b');
+	Some(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::NumExt;
+
+	/// Parses `input`, returning the error's kind and position on failure.
+	fn parse<T: NumExt>(input: &str) -> Result<T, (ParseHumanErrorKind, usize)> {
+		T::parse_human(input).map_err(|error| (error.kind(), error.position()))
+	}
+
+	#[test]
+	fn plain() {
+		assert_eq!(parse::<u8>("0"), Ok(0));
+		assert_eq!(parse::<u8>("255"), Ok(255));
+		assert_eq!(parse::<i8>("-128"), Ok(-128));
+		assert_eq!(parse::<i8>("+127"), Ok(127));
+		assert_eq!(parse::<u8>("-0"), Ok(0));
+		assert_eq!(parse::<u32>("  42  "), Ok(42));
+		assert_eq!(parse::<u32>("007"), Ok(7));
+		assert_eq!(parse::<u128>("340282366920938463463374607431768211455"), Ok(u128::MAX));
+		assert_eq!(parse::<i128>("-170141183460469231731687303715884105728"), Ok(i128::MIN));
+	}
+
+	#[test]
+	fn separators() {
+		assert_eq!(parse::<u32>("1_000_000"), Ok(1_000_000));
+		assert_eq!(parse::<u32>("1_0"), Ok(10));
+		assert_eq!(parse::<u32>("1_000.5_0k"), Ok(1_000_500));
+		assert_eq!(parse::<u32>("0xFF_FF"), Ok(0xFFFF));
+		assert_eq!(parse::<u32>("1_"), Err((InvalidDigit, 1)));
+		assert_eq!(parse::<u32>("1__0"), Err((InvalidDigit, 1)));
+		assert_eq!(parse::<u32>("_1"), Err((InvalidDigit, 0)));
+		assert_eq!(parse::<u32>("1_.5k"), Err((InvalidDigit, 1)));
+		assert_eq!(parse::<u32>("1._5k"), Err((InvalidDigit, 2)));
+		assert_eq!(parse::<u32>("1_k"), Err((InvalidDigit, 1)));
+		assert_eq!(parse::<u32>("0x_1F"), Err((InvalidDigit, 2)));
+		assert_eq!(parse::<u32>("0x1F_"), Err((InvalidDigit, 4)));
+	}
+
+	#[test]
+	fn radix_prefixes() {
+		assert_eq!(parse::<u8>("0x1F"), Ok(0x1F));
+		assert_eq!(parse::<u8>("0X1f"), Ok(0x1F));
+		assert_eq!(parse::<u8>("0b1010"), Ok(0b1010));
+		assert_eq!(parse::<u16>("0o777"), Ok(0o777));
+		assert_eq!(parse::<i8>("-0x80"), Ok(-128));
+		assert_eq!(parse::<u128>("0x100000000000000000000000000000000"), Err((Overflow, 34)));
+		assert_eq!(parse::<u8>("0b102"), Err((InvalidDigit, 4)));
+		assert_eq!(parse::<u8>("0x"), Err((InvalidDigit, 2)));
+		assert_eq!(parse::<u8>("0xG"), Err((InvalidDigit, 2)));
+		assert_eq!(parse::<u8>("0x1k"), Err((InvalidSuffix, 3)));
+	}
+
+	#[test]
+	fn units() {
+		assert_eq!(parse::<u32>("10k"), Ok(10_000));
+		assert_eq!(parse::<u32>("10 kB"), Ok(10_000));
+		assert_eq!(parse::<u32>("10KiB"), Ok(10_240));
+		assert_eq!(parse::<u64>("1.5GiB"), Ok(1_610_612_736));
+		assert_eq!(parse::<u64>("1.5 gib"), Ok(1_610_612_736));
+		assert_eq!(parse::<u64>("2M"), Ok(2_000_000));
+		assert_eq!(parse::<u64>("100B"), Ok(100));
+		assert_eq!(parse::<u64>("1EB"), Ok(10u64.pow(18)));
+		assert_eq!(parse::<u64>("1Ei"), Ok(1 << 60));
+		assert_eq!(parse::<u128>("1Q"), Ok(10u128.pow(30)));
+		assert_eq!(parse::<u128>("1YiB"), Ok(1 << 80));
+		assert_eq!(parse::<u32>(".5k"), Ok(500));
+		assert_eq!(parse::<u32>("10x"), Err((InvalidSuffix, 2)));
+		assert_eq!(parse::<u32>("10 kBs"), Err((InvalidSuffix, 5)));
+		assert_eq!(parse::<u64>("1E"), Err((InvalidSuffix, 1)));
+		assert_eq!(parse::<u64>("1 e"), Err((InvalidSuffix, 2)));
+	}
+
+	#[test]
+	fn fractions_and_exponents() {
+		assert_eq!(parse::<u32>("1e6"), Ok(1_000_000));
+		assert_eq!(parse::<u32>("1E+6"), Ok(1_000_000));
+		assert_eq!(parse::<u32>("2.5e2"), Ok(250));
+		assert_eq!(parse::<u32>("2500e-2"), Ok(25));
+		assert_eq!(parse::<u32>("1.000"), Ok(1));
+		assert_eq!(parse::<u32>("0e99999999999"), Ok(0));
+		assert_eq!(parse::<i32>("-1.25k"), Ok(-1250));
+		assert_eq!(parse::<u32>("1.5"), Err((Fractional, 0)));
+		assert_eq!(parse::<u32>(" 1.0005k"), Err((Fractional, 1)));
+		assert_eq!(parse::<u32>("5e-1"), Err((Fractional, 0)));
+		assert_eq!(parse::<u32>("1e-99999999999"), Err((Fractional, 0)));
+		assert_eq!(parse::<u64>("1e-99999999999999999999"), Err((Fractional, 0)));
+		assert_eq!(parse::<u64>("1.5e-99_999_999_999_999_999_999_999_999_999_999_999_999_999"), Err((Fractional, 0)));
+		assert_eq!(parse::<u64>("0e-99999999999999999999"), Ok(0));
+		assert_eq!(parse::<u64>("0e99999999999999999999"), Ok(0));
+		assert_eq!(parse::<u64>("-0.0e99999999999999999999"), Ok(0));
+		assert_eq!(parse::<u64>("1e1_0"), Ok(10_000_000_000));
+		assert_eq!(parse::<u64>("1e1__0"), Err((InvalidDigit, 3)));
+		assert_eq!(parse::<u32>("5e-1k"), Err((InvalidSuffix, 4)));
+		assert_eq!(parse::<u32>("1e3 kB"), Err((InvalidSuffix, 4)));
+		assert_eq!(parse::<u32>("1e3B"), Ok(1000));
+	}
+
+	#[test]
+	fn overflow() {
+		assert_eq!(parse::<u8>("256"), Err((Overflow, 2)));
+		assert_eq!(parse::<u8>("  1000"), Err((Overflow, 5)));
+		assert_eq!(parse::<u8>("2_56"), Err((Overflow, 3)));
+		assert_eq!(parse::<i8>("-129"), Err((Overflow, 3)));
+		assert_eq!(parse::<i8>("128"), Err((Overflow, 2)));
+		assert_eq!(parse::<u8>("-1"), Err((Overflow, 1)));
+		assert_eq!(parse::<u8>("0x100"), Err((Overflow, 4)));
+		assert_eq!(parse::<u8>("99999999999999999999999999999999999999999"), Err((Overflow, 2)));
+		assert_eq!(parse::<u128>("340282366920938463463374607431768211456"), Err((Overflow, 38)));
+		assert_eq!(parse::<u8>("2.56e2"), Err((Overflow, 4)));
+		assert_eq!(parse::<u8>("1e99999999999999999999"), Err((Overflow, 1)));
+		assert_eq!(parse::<u8>("0.3k"), Err((Overflow, 3)));
+		assert_eq!(parse::<u16>("1 MiB"), Err((Overflow, 2)));
+		assert_eq!(parse::<u128>("1_000_000_000Q"), Err((Overflow, 13)));
+	}
+
+	#[test]
+	fn empty_and_invalid() {
+		assert_eq!(parse::<u8>(""), Err((Empty, 0)));
+		assert_eq!(parse::<u8>("   "), Err((Empty, 3)));
+		assert_eq!(parse::<u8>("-"), Err((Empty, 1)));
+		assert_eq!(parse::<u8>("."), Err((Empty, 1)));
+		assert_eq!(parse::<u8>("k"), Err((InvalidDigit, 0)));
+		assert_eq!(parse::<u8>("1 2"), Err((InvalidDigit, 2)));
+		assert_eq!(parse::<u8>("1.2.3"), Err((InvalidDigit, 3)));
+		assert_eq!(parse::<u8>("--1"), Err((InvalidDigit, 1)));
+	}
+
+	#[test]
+	fn error_display() {
+		let error = u8::parse_human("25x").unwrap_err();
+		assert_eq!(error.to_string(), "unrecognized unit suffix at position 2");
+		let error = u8::parse_human("256").unwrap_err();
+		assert_eq!(error.to_string(), "number is out of range for the type at position 2");
+	}
+}
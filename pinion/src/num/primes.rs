@@ -0,0 +1,559 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Primality and factorization for the full 128-bit range. Requires the `primes`
+//! feature.
+
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use super::arith::gcd;
+use montgomery::Montgomery;
+
+mod ecm;
+mod montgomery;
+
+/// Small primes used for trial division before the probable-prime tests, and as
+/// the Miller-Rabin bases.
+const SMALL_PRIMES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// The smallest strong pseudoprime to all of [`SMALL_PRIMES`] as bases, ψ₁₃.
+/// Below it, Miller-Rabin with those bases is deterministic.
+const DETERMINISTIC_BOUND: u128 = 3_317_044_064_679_887_385_961_981;
+
+/// Returns `true` if `n` is a prime. Values in the 64-bit range are checked with
+/// [`primal`], values below [`DETERMINISTIC_BOUND`] (about 2⁸¹) with a Miller-Rabin
+/// test that is deterministic for them, and anything wider with a Baillie-PSW
+/// test. Baillie-PSW has no known counterexample, but it isn't proven correct for
+/// every 128-bit value.
+pub fn is_prime(n: u128) -> bool {
+	if let Ok(n) = u64::try_from(n) {
+		return primal::is_prime(n)
+	}
+
+	if SMALL_PRIMES.iter().any(|&p| n.is_multiple_of(p)) {
+		return false
+	}
+
+	let m = Montgomery::new(n);
+	if n < DETERMINISTIC_BOUND {
+		return SMALL_PRIMES.iter().all(|&base| is_strong_probable_prime(&m, base))
+	}
+
+	is_strong_probable_prime(&m, 2) && is_strong_lucas_probable_prime(&m)
+}
+
+/// Returns the smallest prime greater than `n`, or `None` on overflow.
+pub fn next_prime(n: u128) -> Option<u128> {
+	let mut candidate = n;
+	loop {
+		candidate = candidate.checked_add(1)?;
+		if is_prime(candidate) {
+			return Some(candidate)
+		}
+	}
+}
+
+/// Returns the largest prime less than `n`, or `None` if there is none.
+pub fn prev_prime(n: u128) -> Option<u128> {
+	(2..n).rev().find(|&candidate| is_prime(candidate))
+}
+
+/// Returns the prime factors of `n` in ascending order, with their multiplicities.
+/// Zero and one have no prime factors.
+pub fn prime_factors(mut n: u128) -> Vec<(u128, u32)> {
+	let mut factors = Vec::new();
+	if n < 2 {
+		return factors
+	}
+
+	for p in SMALL_PRIMES {
+		let mut multiplicity = 0;
+		while n.is_multiple_of(p) {
+			n /= p;
+			multiplicity += 1;
+		}
+
+		if multiplicity > 0 {
+			factors.push((p, multiplicity));
+		}
+	}
+
+	let start = factors.len();
+	split_factors(n, &mut factors);
+	factors[start..].sort_unstable();
+
+	// Merge repeated factors found by splitting into multiplicities.
+	let mut merged: Vec<(u128, u32)> = Vec::with_capacity(factors.len());
+	for (p, multiplicity) in factors {
+		match merged.last_mut() {
+			Some((last, count)) if *last == p => *count += multiplicity,
+			_ => merged.push((p, multiplicity))
+		}
+	}
+	merged
+}
+
+/// Computes Euler's totient of `n`, the count of integers in `1..=n` coprime to
+/// it. Returns zero for zero.
+pub fn totient(n: u128) -> u128 {
+	prime_factors(n)
+		.into_iter()
+		.fold(n, |phi, (p, _)| phi / p * (p - 1))
+}
+
+/// Splits `n`, which has no factor in [`SMALL_PRIMES`], into prime factors,
+/// pushing each one found with multiplicity one.
+fn split_factors(n: u128, factors: &mut Vec<(u128, u32)>) {
+	if n == 1 {
+		return
+	}
+
+	if is_prime(n) {
+		factors.push((n, 1));
+		return
+	}
+
+	if let Some((root, exponent)) = perfect_power(n) {
+		for _ in 0..exponent {
+			split_factors(root, factors);
+		}
+		return
+	}
+
+	let divisor = find_divisor(n);
+	split_factors(divisor, factors);
+	split_factors(n / divisor, factors);
+}
+
+/// Finds a non-trivial divisor of `n`, an odd composite with no factor in
+/// [`SMALL_PRIMES`] that isn't a perfect power. Pollard's rho finds factors of up
+/// to about 36 bits quickly; anything it misses is left to the elliptic curve
+/// method.
+fn find_divisor(n: u128) -> u128 {
+	let m = Montgomery::new(n);
+	(1..=3)
+		.find_map(|c| pollard_brent(&m, c))
+		.unwrap_or_else(|| ecm::find_divisor(&m))
+}
+
+/// Returns `n` as `root ^ exponent` with a prime exponent, if it is a perfect
+/// power. `n` must have no factor in [`SMALL_PRIMES`], which bounds the exponent.
+fn perfect_power(n: u128) -> Option<(u128, u32)> {
+	let root = n.isqrt();
+	if root * root == n {
+		return Some((root, 2))
+	}
+
+	// Roots are at most cube roots here, small enough to estimate with a float and
+	// correct by one.
+	SMALL_PRIMES[1..]
+		.iter()
+		.map(|&exponent| exponent as u32)
+		.take_while(|&exponent| 43u128.checked_pow(exponent).is_some_and(|min| min <= n))
+		.find_map(|exponent| {
+			let estimate = (n as f64).powf(1.0 / exponent as f64).round() as u128;
+			(estimate.saturating_sub(1)..=estimate + 1)
+				.find(|root| root.checked_pow(exponent) == Some(n))
+				.map(|root| (root, exponent))
+		})
+}
+
+/// Searches for a non-trivial divisor of the odd composite modulus of `m` with
+/// Brent's variant of Pollard's rho, iterating `x² + c`. Returns `None` if the
+/// cycle closes without finding one, in which case another `c` should be tried,
+/// or if none is found within about a million steps.
+fn pollard_brent(m: &Montgomery, c: u128) -> Option<u128> {
+	const BATCH: u128 = 128;
+	const MAX_CYCLE: u128 = 1 << 19;
+
+	let n = m.modulus();
+	let c = m.encode(c);
+	let f = |x| m.add(m.mul(x, x), c);
+	let (mut x, mut y, mut ys) = (m.one(), m.one(), m.one());
+	let (mut r, mut q, mut g) = (1u128, m.one(), 1);
+	while g == 1 {
+		if r > MAX_CYCLE {
+			return None
+		}
+
+		x = y;
+		for _ in 0..r {
+			y = f(y);
+		}
+
+		let mut k = 0;
+		while k < r && g == 1 {
+			ys = y;
+			for _ in 0..BATCH.min(r - k) {
+				y = f(y);
+				q = m.mul(q, x.abs_diff(y));
+			}
+			g = gcd(q, n);
+			k += BATCH;
+		}
+		r *= 2;
+	}
+
+	if g == n {
+		// The batch overshot; step through it one value at a time.
+		loop {
+			ys = f(ys);
+			g = gcd(x.abs_diff(ys), n);
+			if g > 1 {
+				break
+			}
+		}
+	}
+
+	(g != n).then_some(g)
+}
+
+/// An iterator over the primes within a range, created by [`NumExt::primes_in`].
+///
+/// [`NumExt::primes_in`]: crate::NumExt::primes_in
+#[derive(Clone, Debug)]
+pub struct PrimesIn<T> {
+	next: Option<u128>,
+	end: Option<u128>,
+	_type: PhantomData<T>
+}
+
+impl<T: Copy> PrimesIn<T> where u128: TryFrom<T> {
+	pub(crate) fn new<R: RangeBounds<T>>(range: R) -> Self {
+		// Negative bounds are clamped to zero, there are no negative primes.
+		let lower = |n| u128::try_from(n).unwrap_or(0);
+		let next = match range.start_bound() {
+			Bound::Included(&start) => Some(lower(start)),
+			Bound::Excluded(&start) => lower(start).checked_add(1),
+			Bound::Unbounded => Some(0)
+		};
+		let end = match range.end_bound() {
+			Bound::Included(&end) => u128::try_from(end).ok(),
+			Bound::Excluded(&end) => u128::try_from(end).ok().and_then(|end| end.checked_sub(1)),
+			Bound::Unbounded => None
+		};
+		// A bounded range ending below zero is empty.
+		let next = next.filter(|_| end.is_some() || matches!(range.end_bound(), Bound::Unbounded));
+		Self { next, end, _type: PhantomData }
+	}
+}
+
+impl<T: TryFrom<u128>> Iterator for PrimesIn<T> {
+	type Item = T;
+
+	fn next(&mut self) -> Option<T> {
+		while let Some(candidate) = self.next {
+			if self.end.is_some_and(|end| candidate > end) {
+				break
+			}
+
+			// Stop once the candidate can no longer be represented.
+			let Ok(value) = T::try_from(candidate) else { break };
+			self.next = candidate.checked_add(1);
+			if is_prime(candidate) {
+				return Some(value)
+			}
+		}
+
+		self.next = None;
+		None
+	}
+}
+
+impl<T: TryFrom<u128>> FusedIterator for PrimesIn<T> { }
+
+/// Halves `x (mod m)` for odd `m`, where `x` is reduced. Halving commutes with
+/// the Montgomery form.
+fn half_mod(x: u128, m: u128) -> u128 {
+	if x & 1 == 0 {
+		x >> 1
+	} else {
+		// (x + m) / 2, without overflowing. Both are odd.
+		(x >> 1) + (m >> 1) + 1
+	}
+}
+
+/// Computes the Jacobi symbol `(a / n)` for odd `n`.
+fn jacobi(mut a: u128, mut n: u128) -> i8 {
+	a %= n;
+	let mut sign = 1;
+	while a != 0 {
+		while a & 1 == 0 {
+			a >>= 1;
+			if matches!(n % 8, 3 | 5) {
+				sign = -sign;
+			}
+		}
+		(a, n) = (n, a);
+		if a % 4 == 3 && n % 4 == 3 {
+			sign = -sign;
+		}
+		a %= n;
+	}
+
+	if n == 1 { sign } else { 0 }
+}
+
+/// Runs a strong Fermat (Miller-Rabin) round on the odd modulus of `m` against
+/// `base`.
+fn is_strong_probable_prime(m: &Montgomery, base: u128) -> bool {
+	let n = m.modulus();
+	let minus_one = n - m.one();
+	let s = (n - 1).trailing_zeros();
+	let d = (n - 1) >> s;
+	let mut x = m.pow(m.encode(base), d);
+	if x == m.one() || x == minus_one {
+		return true
+	}
+
+	for _ in 1..s {
+		x = m.mul(x, x);
+		if x == minus_one {
+			return true
+		}
+	}
+	false
+}
+
+/// Runs a strong Lucas probable-prime test on the odd modulus of `m`, which has
+/// no small prime factor, choosing parameters with Selfridge's method.
+fn is_strong_lucas_probable_prime(m: &Montgomery) -> bool {
+	let n = m.modulus();
+	let root = n.isqrt();
+	if root * root == n {
+		return false
+	}
+
+	// Find the first D in 5, -7, 9, -11, ... with (D / n) = -1. D is kept as a
+	// residue modulo n. Since n is not a square, this terminates quickly. A zero
+	// symbol means D shares a factor with n, which is wider than any D we try.
+	let mut magnitude = 5u128;
+	let mut negative = false;
+	let d = loop {
+		let d = if negative { n - magnitude % n } else { magnitude % n };
+		match jacobi(d, n) {
+			-1 => break d,
+			0 => return false,
+			_ => { }
+		}
+		magnitude += 2;
+		negative = !negative;
+	};
+
+	// P = 1, Q = (1 - D) / 4
+	let q = if negative {
+		// D = -magnitude, so Q = (1 + magnitude) / 4
+		m.encode((1 + magnitude) / 4)
+	} else {
+		// D = magnitude, so Q = -(magnitude - 1) / 4
+		m.sub(0, m.encode((magnitude - 1) / 4))
+	};
+	let d = m.encode(d);
+
+	// n + 1 cannot overflow, u128::MAX is divisible by 3.
+	let s = (n + 1).trailing_zeros();
+	let k = (n + 1) >> s;
+
+	let (mut u, mut v, mut q_k) = (m.one(), m.one(), q);
+	for bit in (0..k.ilog2()).rev() {
+		// Double the index: U(2k) = U(k) V(k), V(2k) = V(k)² - 2Qᵏ
+		u = m.mul(u, v);
+		v = m.sub(m.mul(v, v), m.add(q_k, q_k));
+		q_k = m.mul(q_k, q_k);
+
+		if k >> bit & 1 == 1 {
+			// Increment the index: U(k+1) = (U + V) / 2, V(k+1) = (D U + V) / 2
+			let (prev_u, prev_v) = (u, v);
+			u = half_mod(m.add(prev_u, prev_v), n);
+			v = half_mod(m.add(m.mul(d, prev_u), prev_v), n);
+			q_k = m.mul(q_k, q);
+		}
+	}
+
+	if u == 0 || v == 0 {
+		return true
+	}
+
+	for _ in 1..s {
+		v = m.sub(m.mul(v, v), m.add(q_k, q_k));
+		q_k = m.mul(q_k, q_k);
+		if v == 0 {
+			return true
+		}
+	}
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::NumExt;
+
+	const MERSENNE_89: u128 = (1 << 89) - 1;
+	const MERSENNE_127: u128 = (1 << 127) - 1;
+	/// The smallest prime above 2⁶⁴.
+	const ABOVE_64: u128 = (1 << 64) + 13;
+	/// The largest prime below 2⁶⁴.
+	const BELOW_64: u128 = u64::MAX as u128 - 58;
+	/// ψ₁₂, the smallest strong pseudoprime to the prime bases 2 through 37.
+	const PSI_12: u128 = 318_665_857_834_031_151_167_461;
+
+	#[test]
+	fn is_prime_above_64_bits() {
+		for n in [MERSENNE_89, MERSENNE_127, ABOVE_64, DETERMINISTIC_BOUND + 142, (1 << 100) + 277, (1 << 127) + 29] {
+			assert!(is_prime(n), "{n} is prime");
+		}
+		for n in [
+			MERSENNE_89 + 2,
+			(1 << 64) + 1,
+			u128::MAX,
+			BELOW_64 * BELOW_64,
+			MERSENNE_89 * 3,
+			((1 << 61) - 1) * BELOW_64,
+		] {
+			assert!(!is_prime(n), "{n} is composite");
+		}
+	}
+
+	#[test]
+	fn is_prime_below_64_bits() {
+		assert!(is_prime(2));
+		assert!(is_prime(BELOW_64));
+		assert!(!is_prime(0));
+		assert!(!is_prime(1));
+		assert!(!is_prime(u64::MAX as u128));
+		// ψ₁₁, the smallest strong pseudoprime to the prime bases 2 through 31.
+		assert!(!is_prime(3_825_123_056_546_413_051));
+	}
+
+	#[test]
+	fn strong_pseudoprimes() {
+		for n in [PSI_12, DETERMINISTIC_BOUND] {
+			assert!(is_strong_probable_prime(&Montgomery::new(n), 2), "{n} is a strong pseudoprime to base 2");
+			assert!(!is_prime(n), "{n} is composite");
+		}
+
+		let (psi_12, psi_13) = (Montgomery::new(PSI_12), Montgomery::new(DETERMINISTIC_BOUND));
+		assert!(SMALL_PRIMES[..12].iter().all(|&base| is_strong_probable_prime(&psi_12, base)));
+		assert!(SMALL_PRIMES.iter().all(|&base| is_strong_probable_prime(&psi_13, base)));
+		assert!(!is_strong_lucas_probable_prime(&psi_13));
+	}
+
+	#[test]
+	fn squares_of_large_primes() {
+		for p in [(1 << 61) - 1, (1 << 63) - 25, BELOW_64, 4_294_967_291] {
+			assert!(!is_prime(p * p), "{p}² is composite");
+			assert!(!is_strong_lucas_probable_prime(&Montgomery::new(p * p)), "{p}² fails the Lucas test");
+		}
+	}
+
+	#[test]
+	fn factors_hard_semiprimes() {
+		let p = (1 << 61) - 1;
+		assert_eq!(prime_factors(p * BELOW_64), [(p, 1), (BELOW_64, 1)]);
+		assert_eq!(prime_factors(BELOW_64 * BELOW_64), [(BELOW_64, 2)]);
+
+		let q = 4_294_967_291;
+		assert_eq!(prime_factors(q * q * q), [(q, 3)]);
+		assert_eq!(prime_factors(q * 4_294_967_279 * 65_521), [(65_521, 1), (4_294_967_279, 1), (q, 1)]);
+	}
+
+	#[test]
+	fn prime_factors_with_multiplicity() {
+		assert_eq!(prime_factors(0), []);
+		assert_eq!(prime_factors(1), []);
+		assert_eq!(prime_factors(2), [(2, 1)]);
+		assert_eq!(prime_factors(360), [(2, 3), (3, 2), (5, 1)]);
+		assert_eq!(prime_factors(1 << 127), [(2, 127)]);
+		assert_eq!(prime_factors(MERSENNE_127), [(MERSENNE_127, 1)]);
+		assert_eq!(
+			prime_factors(u128::MAX),
+			[(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65_537, 1), (274_177, 1), (6_700_417, 1), (67_280_421_310_721, 1)]
+		);
+		assert_eq!(prime_factors(43 * 43 * 47 * ABOVE_64), [(43, 2), (47, 1), (ABOVE_64, 1)]);
+	}
+
+	#[test]
+	fn next_and_prev_prime() {
+		assert_eq!(0u8.next_prime(), Some(2));
+		assert_eq!((-5i32).next_prime(), Some(2));
+		assert_eq!(2u8.next_prime(), Some(3));
+		assert_eq!(250u8.next_prime(), Some(251));
+		assert_eq!(251u8.next_prime(), None);
+		assert_eq!(i8::MAX.next_prime(), None);
+		assert_eq!(u64::MAX.next_prime(), None);
+		assert_eq!((u64::MAX as u128).next_prime(), Some(ABOVE_64));
+		assert_eq!((u128::MAX - 159).next_prime(), Some(u128::MAX - 158));
+		assert_eq!((u128::MAX - 158).next_prime(), None);
+
+		assert_eq!(2u8.prev_prime(), None);
+		assert_eq!((-7i64).prev_prime(), None);
+		assert_eq!(3u8.prev_prime(), Some(2));
+		assert_eq!(u8::MAX.prev_prime(), Some(251));
+		assert_eq!(i16::MAX.prev_prime(), Some(32_749));
+		assert_eq!(ABOVE_64.prev_prime(), Some(BELOW_64));
+	}
+
+	#[test]
+	fn signed_prime_factors() {
+		assert_eq!((-12i32).prime_factors(), [(2, 2), (3, 1)]);
+		assert_eq!(i8::MIN.prime_factors(), [(2, 7)]);
+		assert_eq!(i128::MIN.prime_factors(), [(2, 127)]);
+		assert_eq!((-1i64).prime_factors(), []);
+		assert_eq!(u16::MAX.prime_factors(), [(3, 1), (5, 1), (17, 1), (257, 1)]);
+	}
+
+	#[test]
+	fn totient() {
+		assert_eq!(0u32.totient(), None);
+		assert_eq!((-9i32).totient(), None);
+		assert_eq!(1u8.totient(), Some(1));
+		assert_eq!(36u64.totient(), Some(12));
+		assert_eq!(97i8.totient(), Some(96));
+		assert_eq!(MERSENNE_127.totient(), Some(MERSENNE_127 - 1));
+		assert_eq!((1u128 << 127).totient(), Some(1 << 126));
+		assert_eq!(super::totient(0), 0);
+	}
+
+	#[test]
+	fn is_coprime() {
+		assert!(8u8.is_coprime(9));
+		assert!(!6u32.is_coprime(9));
+		assert!((-8i16).is_coprime(9));
+		assert!(!(-8i16).is_coprime(-6));
+		assert!(1u64.is_coprime(0));
+		assert!(!0u64.is_coprime(0));
+		assert!(!i64::MIN.is_coprime(2));
+		assert!(MERSENNE_127.is_coprime(MERSENNE_89));
+	}
+
+	#[test]
+	fn primes_in() {
+		assert_eq!(u8::primes_in(..12).collect::<Vec<_>>(), [2, 3, 5, 7, 11]);
+		assert_eq!(u8::primes_in(11..=13).collect::<Vec<_>>(), [11, 13]);
+		assert_eq!(u8::primes_in(240..).collect::<Vec<_>>(), [241, 251]);
+		assert_eq!(u8::primes_in(0..0).next(), None);
+		assert_eq!(u8::primes_in(14..14).next(), None);
+		assert_eq!(i8::primes_in(120..).collect::<Vec<_>>(), [127]);
+		assert_eq!(u64::primes_in(u64::MAX - 100..).collect::<Vec<_>>(), [u64::MAX - 94, u64::MAX - 82, u64::MAX - 58]);
+		assert_eq!(u128::primes_in((1 << 64)..(1 << 64) + 14).collect::<Vec<_>>(), [ABOVE_64]);
+
+		let mut primes = u8::primes_in(250..);
+		assert_eq!(primes.next(), Some(251));
+		assert_eq!(primes.next(), None);
+		assert_eq!(primes.next(), None);
+	}
+
+	#[test]
+	fn primes_in_signed_ranges() {
+		assert_eq!(i32::primes_in(-10..10).collect::<Vec<_>>(), [2, 3, 5, 7]);
+		assert_eq!(i32::primes_in(..=5).collect::<Vec<_>>(), [2, 3, 5]);
+		assert_eq!(i64::primes_in(-10..=-1).next(), None);
+		assert_eq!(i64::primes_in(-10..0).next(), None);
+		assert_eq!(i64::primes_in(-10..1).next(), None);
+		assert_eq!(i8::primes_in(i8::MIN..).last(), Some(127));
+		assert_eq!(i8::primes_in(i8::MIN..=i8::MAX).count(), 31);
+		assert_eq!(i16::primes_in((Bound::Excluded(-3), Bound::Excluded(3))).collect::<Vec<_>>(), [2]);
+		assert_eq!(i16::primes_in((Bound::Excluded(2), Bound::Included(3))).collect::<Vec<_>>(), [3]);
+	}
+}
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lenstra's elliptic curve method, for composites whose smallest factor is too
+//! large for Pollard's rho. Its running time depends on the size of the smallest
+//! factor rather than of the composite, and the parameters here are tuned for
+//! factors of up to 64 bits, the largest a 128-bit composite's smallest factor
+//! can be.
+
+use primal::Sieve;
+use crate::num::arith::{gcd, inv_mod};
+use super::montgomery::Montgomery;
+
+/// The stage one bound. Each curve multiplies its point by every prime power up
+/// to this bound.
+const B1: usize = 11_000;
+/// The stage two bound. Each curve then checks for a single larger prime factor
+/// of the group order, up to this bound.
+const B2: usize = 100 * B1;
+/// The giant step size of stage two, `2·3·5·7·11`. Baby steps only need to cover
+/// the residues coprime to it.
+const D: usize = 2310;
+
+/// A point on a Montgomery curve in projective `(X : Z)` coordinates, with the
+/// `y` coordinate dropped.
+#[derive(Copy, Clone, Debug)]
+struct Point {
+	x: u128,
+	z: u128
+}
+
+/// A curve `By² = x³ + Ax² + x` modulo a composite, in Montgomery form.
+struct Curve<'a> {
+	m: &'a Montgomery,
+	/// `(A + 2) / 4`
+	a24: u128
+}
+
+/// Searches for a non-trivial divisor of the odd composite modulus of `m`, which
+/// must not be a perfect power, trying curves until one succeeds.
+pub fn find_divisor(m: &Montgomery) -> u128 {
+	let sieve = Sieve::new(B2 + D);
+	(6..)
+		.find_map(|sigma| try_curve(m, sigma, &sieve))
+		.expect("a curve should eventually find a divisor")
+}
+
+/// Runs both stages on the curve chosen by `sigma`, returning a divisor if the
+/// curve's group order modulo some factor is smooth enough.
+fn try_curve(m: &Montgomery, sigma: u128, sieve: &Sieve) -> Option<u128> {
+	let n = m.modulus();
+	let divisor = |z: u128| Some(gcd(z, n)).filter(|&g| g != 1 && g != n);
+	let (curve, mut point) = match Curve::suyama(m, sigma) {
+		Ok(curve) => curve,
+		Err(divisor) => return divisor
+	};
+
+	// Stage one: multiply by every prime power up to B1. If the group order modulo
+	// a factor p is B1-smooth, the point is now the identity modulo p, with Z ≡ 0.
+	for p in sieve.primes_from(2).take_while(|&p| p <= B1) {
+		let mut power = p;
+		while power * p <= B1 {
+			power *= p;
+		}
+		point = curve.ladder(point, power as u128).0;
+	}
+
+	if let Some(divisor) = divisor(point.z) {
+		return Some(divisor)
+	}
+
+	// Stage two: check for one remaining prime q in B1..=B2. Writing q = kD ± j,
+	// kD·P and j·P share an x coordinate modulo p if qP is the identity there.
+	let doubled = curve.double(point);
+	let mut baby_steps = Vec::with_capacity(D / 4);
+	let (mut previous, mut current) = (point, curve.add(doubled, point, point));
+	baby_steps.push((1, point));
+	for j in (3..D / 2).step_by(2) {
+		if gcd(j as u128, D as u128) == 1 {
+			baby_steps.push((j, current));
+		}
+		(previous, current) = (current, curve.add(current, doubled, previous));
+	}
+
+	let giant = curve.ladder(point, D as u128).0;
+	let mut k = B1 / D;
+	let (mut giant_k, mut giant_next) = curve.ladder(giant, k as u128);
+	let mut product = m.one();
+	while k * D <= B2 + D / 2 {
+		for &(j, baby) in &baby_steps {
+			let in_stage = |q: usize| q > B1 && q <= B2 && sieve.is_prime(q);
+			if in_stage(k * D - j) || in_stage(k * D + j) {
+				let difference = m.sub(m.mul(giant_k.x, baby.z), m.mul(baby.x, giant_k.z));
+				product = m.mul(product, difference);
+			}
+		}
+
+		(giant_k, giant_next) = (giant_next, curve.add(giant_next, giant, giant_k));
+		k += 1;
+	}
+
+	divisor(product)
+}
+
+impl<'a> Curve<'a> {
+	/// Builds a curve and a point on it with Suyama's parametrization, which
+	/// guarantees a group order divisible by 12. Returns `Err` if inverting the
+	/// curve's parameters fails, with a divisor of the modulus if it exposed one.
+	fn suyama(m: &'a Montgomery, sigma: u128) -> Result<(Self, Point), Option<u128>> {
+		let n = m.modulus();
+		let sigma = m.encode(sigma);
+		let u = m.sub(m.mul(sigma, sigma), m.encode(5));
+		let v = m.add(m.add(sigma, sigma), m.add(sigma, sigma));
+		let u_cubed = m.mul(m.mul(u, u), u);
+		let v_minus_u = m.sub(v, u);
+
+		// A + 2 = (v - u)³ (3u + v) / (4u³v), so (A + 2) / 4 has 16u³v below.
+		let numerator = m.mul(
+			m.mul(m.mul(v_minus_u, v_minus_u), v_minus_u),
+			m.add(m.add(m.add(u, u), u), v)
+		);
+		let denominator = m.mul(m.mul(u_cubed, v), m.encode(16));
+		let denominator = m.decode(denominator);
+		let Some(inverse) = inv_mod(denominator, n) else {
+			let g = gcd(denominator, n);
+			return Err((g != n).then_some(g))
+		};
+
+		let a24 = m.mul(numerator, m.encode(inverse));
+		let point = Point { x: u_cubed, z: m.mul(m.mul(v, v), v) };
+		Ok((Self { m, a24 }, point))
+	}
+
+	/// Doubles `p`.
+	fn double(&self, p: Point) -> Point {
+		let m = self.m;
+		let sum = m.add(p.x, p.z);
+		let difference = m.sub(p.x, p.z);
+		let sum_squared = m.mul(sum, sum);
+		let difference_squared = m.mul(difference, difference);
+		let cross = m.sub(sum_squared, difference_squared);
+		Point {
+			x: m.mul(sum_squared, difference_squared),
+			z: m.mul(cross, m.add(difference_squared, m.mul(self.a24, cross)))
+		}
+	}
+
+	/// Adds `p` and `q`, given their difference `p - q`.
+	fn add(&self, p: Point, q: Point, difference: Point) -> Point {
+		let m = self.m;
+		let u = m.mul(m.sub(p.x, p.z), m.add(q.x, q.z));
+		let v = m.mul(m.add(p.x, p.z), m.sub(q.x, q.z));
+		let sum = m.add(u, v);
+		let cross = m.sub(u, v);
+		Point {
+			x: m.mul(difference.z, m.mul(sum, sum)),
+			z: m.mul(difference.x, m.mul(cross, cross))
+		}
+	}
+
+	/// Multiplies `p` by a positive `k` with the Montgomery ladder, returning both
+	/// `k·p` and `(k + 1)·p`.
+	fn ladder(&self, p: Point, k: u128) -> (Point, Point) {
+		let (mut low, mut high) = (p, self.double(p));
+		for bit in (0..k.ilog2()).rev() {
+			if k >> bit & 1 == 1 {
+				low = self.add(high, low, p);
+				high = self.double(high);
+			} else {
+				high = self.add(high, low, p);
+				low = self.double(low);
+			}
+		}
+		(low, high)
+	}
+}
@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Montgomery multiplication modulo an odd 128-bit modulus, with `R = 2¹²⁸`. It
+//! replaces the division in each modular product with two wide multiplications,
+//! which makes it far faster than [`mul_mod`](crate::num::arith::mul_mod) once
+//! the product no longer fits in 128 bits.
+
+use crate::num::arith::{add_mod, sub_mod};
+
+/// Arithmetic modulo an odd modulus on values in Montgomery form, `xR mod n`.
+/// Addition and subtraction work on this form unchanged.
+#[derive(Copy, Clone, Debug)]
+pub struct Montgomery {
+	modulus: u128,
+	/// `-n⁻¹ mod R`
+	neg_inverse: u128,
+	/// `R mod n`, one in Montgomery form.
+	one: u128,
+	/// `R² mod n`, used to convert into Montgomery form.
+	r_squared: u128
+}
+
+impl Montgomery {
+	/// Prepares arithmetic modulo `modulus`, which must be odd and greater than
+	/// one.
+	pub fn new(modulus: u128) -> Self {
+		debug_assert!(modulus & 1 == 1 && modulus > 1, "the modulus must be odd and greater than one");
+
+		// Newton's iteration doubles the correct low bits of the inverse each step,
+		// starting from the three bits any odd number is its own inverse modulo.
+		let mut inverse = modulus;
+		for _ in 0..6 {
+			inverse = inverse.wrapping_mul(2u128.wrapping_sub(modulus.wrapping_mul(inverse)));
+		}
+
+		let one = (u128::MAX % modulus + 1) % modulus;
+		let r_squared = (0..128).fold(one, |r, _| add_mod(r, r, modulus));
+		Self { modulus, neg_inverse: inverse.wrapping_neg(), one, r_squared }
+	}
+
+	/// Returns the modulus.
+	pub fn modulus(&self) -> u128 { self.modulus }
+
+	/// Returns one in Montgomery form.
+	pub fn one(&self) -> u128 { self.one }
+
+	/// Converts `x` into Montgomery form.
+	pub fn encode(&self, x: u128) -> u128 {
+		self.mul(x % self.modulus, self.r_squared)
+	}
+
+	/// Converts `x` out of Montgomery form.
+	pub fn decode(&self, x: u128) -> u128 {
+		self.reduce(x, 0)
+	}
+
+	/// Multiplies `a` and `b` in Montgomery form.
+	pub fn mul(&self, a: u128, b: u128) -> u128 {
+		let (low, high) = mul_wide(a, b);
+		self.reduce(low, high)
+	}
+
+	/// Adds `a` and `b` in Montgomery form.
+	pub fn add(&self, a: u128, b: u128) -> u128 { add_mod(a, b, self.modulus) }
+
+	/// Subtracts `b` from `a` in Montgomery form.
+	pub fn sub(&self, a: u128, b: u128) -> u128 { sub_mod(a, b, self.modulus) }
+
+	/// Raises `base`, in Montgomery form, to `exp`.
+	pub fn pow(&self, mut base: u128, mut exp: u128) -> u128 {
+		let mut result = self.one;
+		while exp > 0 {
+			if exp & 1 == 1 {
+				result = self.mul(result, base);
+			}
+			base = self.mul(base, base);
+			exp >>= 1;
+		}
+		result
+	}
+
+	/// Computes `xR⁻¹ mod n` for `x = high·R + low`, where `high < n`.
+	fn reduce(&self, low: u128, high: u128) -> u128 {
+		// Adding m·n clears the low half, leaving the high half congruent to xR⁻¹.
+		// That sum may overflow 256 bits, so track the carry out of the low half.
+		let m = low.wrapping_mul(self.neg_inverse);
+		let (mn_low, mn_high) = mul_wide(m, self.modulus);
+		let carry = u128::from(low.overflowing_add(mn_low).1);
+		let (sum, overflow) = high.overflowing_add(mn_high);
+		let (sum, carry_overflow) = sum.overflowing_add(carry);
+		// The result is below 2n; reduce it once.
+		if overflow || carry_overflow || sum >= self.modulus {
+			sum.wrapping_sub(self.modulus)
+		} else {
+			sum
+		}
+	}
+}
+
+/// Multiplies `a` and `b` into a 256-bit product, returned as its low and high
+/// halves.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+	const MASK: u128 = u64::MAX as u128;
+
+	let (a_low, a_high) = (a & MASK, a >> 64);
+	let (b_low, b_high) = (b & MASK, b >> 64);
+	let low = a_low * b_low;
+	let cross_1 = a_low * b_high;
+	let cross_2 = a_high * b_low;
+	let high = a_high * b_high;
+
+	// At most three 64-bit values, which can't overflow.
+	let middle = (low >> 64) + (cross_1 & MASK) + (cross_2 & MASK);
+	(
+		(low & MASK) | (middle << 64),
+		high + (cross_1 >> 64) + (cross_2 >> 64) + (middle >> 64)
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::num::arith::mul_mod;
+
+	#[test]
+	fn mul_wide() {
+		assert_eq!(super::mul_wide(u128::MAX, u128::MAX), (1, u128::MAX - 1));
+		assert_eq!(super::mul_wide(1 << 64, 1 << 64), (0, 1));
+		assert_eq!(super::mul_wide(12345, 67890), (12345 * 67890, 0));
+	}
+
+	#[test]
+	fn round_trip() {
+		for n in [3, 1_000_003, u64::MAX as u128, (1 << 127) - 1, u128::MAX] {
+			let m = Montgomery::new(n);
+			for x in [0, 1, 2, n - 1, n / 2, 0xDEAD_BEEF % n] {
+				assert_eq!(m.decode(m.encode(x)), x, "{x} mod {n}");
+			}
+			assert_eq!(m.decode(m.one()), 1);
+		}
+	}
+
+	#[test]
+	fn mul_matches_mul_mod() {
+		for n in [3, 1_000_003, u64::MAX as u128, (1 << 127) - 1, u128::MAX - 2, u128::MAX] {
+			let m = Montgomery::new(n);
+			for (a, b) in [(n - 1, n - 1), (n / 2, n / 3), (n - 2, 7), (0, n - 1)] {
+				let product = m.mul(m.encode(a), m.encode(b));
+				assert_eq!(m.decode(product), mul_mod(a, b, n), "{a} * {b} mod {n}");
+			}
+		}
+	}
+
+	#[test]
+	fn pow() {
+		let n = (1 << 127) - 1;
+		let m = Montgomery::new(n);
+		// Fermat's little theorem, for the Mersenne prime 2¹²⁷ - 1.
+		assert_eq!(m.pow(m.encode(3), n - 1), m.one());
+		assert_eq!(m.decode(m.pow(m.encode(2), 127)), 1);
+	}
+}
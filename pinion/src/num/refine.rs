@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Refinement types carrying the invariants checked by [`NumExt`]'s filters.
+
+use std::fmt::{self, Display, Formatter};
+use super::NumExt;
+
+/// An integer known to be greater than zero. Constructed with [`Positive::new`],
+/// which checks the value with [`NumExt::positive`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Positive<T>(T);
+
+/// An integer known to be even. Constructed with [`Even::new`], which checks the
+/// value with [`NumExt::even`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Even<T>(T);
+
+/// An integer known to be within `MIN..=MAX`. Constructed with [`Bounded::new`],
+/// which checks the value with [`NumExt::in_range`]. The bounds are given as
+/// `i128` so they can be written for any integer type; bounds outside the range
+/// of `T` are effectively clamped to it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Bounded<T, const MIN: i128, const MAX: i128>(T);
+
+impl<T: NumExt> Positive<T> {
+	/// Optionally wraps `value` if it is positive.
+	pub fn new(value: T) -> Option<Self> {
+		value.positive().map(Self)
+	}
+}
+
+impl<T: NumExt> Even<T> {
+	/// Optionally wraps `value` if it is even.
+	pub fn new(value: T) -> Option<Self> {
+		value.even().map(Self)
+	}
+}
+
+impl<T: NumExt + Copy, const MIN: i128, const MAX: i128> Bounded<T, MIN, MAX> where i128: TryFrom<T> {
+	/// The lower bound.
+	pub const MIN: i128 = MIN;
+	/// The upper bound.
+	pub const MAX: i128 = MAX;
+
+	/// Optionally wraps `value` if it is within `MIN..=MAX`. Since the bounds are
+	/// `i128`, a `Bounded<u128, …>` can't hold values above `i128::MAX`; these are
+	/// always rejected.
+	pub fn new(value: T) -> Option<Self> {
+		const { assert!(MIN <= MAX, "the lower bound must not exceed the upper bound") }
+		i128::try_from(value).ok()?.in_range(MIN..=MAX)?;
+		Some(Self(value))
+	}
+}
+
+macro_rules! refinements {
+	($($ty:ident)+) => {
+		$(
+		impl Positive<$ty> {
+			/// Adds `rhs`, returning `None` on overflow.
+			pub fn checked_add(self, rhs: Self) -> Option<Self> {
+				self.0.checked_add(rhs.0).map(Self)
+			}
+
+			/// Adds `rhs`, saturating at the numeric bound.
+			pub fn saturating_add(self, rhs: Self) -> Self {
+				Self(self.0.saturating_add(rhs.0))
+			}
+
+			/// Subtracts `rhs`, returning `None` on overflow or if the result is not
+			/// positive.
+			pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+				self.0.checked_sub(rhs.0).and_then(Self::new)
+			}
+
+			/// Multiplies by `rhs`, returning `None` on overflow.
+			pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+				self.0.checked_mul(rhs.0).map(Self)
+			}
+
+			/// Multiplies by `rhs`, saturating at the numeric bound.
+			pub fn saturating_mul(self, rhs: Self) -> Self {
+				Self(self.0.saturating_mul(rhs.0))
+			}
+		}
+
+		impl Even<$ty> {
+			/// Adds `rhs`, returning `None` on overflow.
+			pub fn checked_add(self, rhs: Self) -> Option<Self> {
+				self.0.checked_add(rhs.0).map(Self)
+			}
+
+			/// Adds `rhs`, wrapping around at the numeric bound. Wrapping preserves
+			/// evenness.
+			pub fn wrapping_add(self, rhs: Self) -> Self {
+				Self(self.0.wrapping_add(rhs.0))
+			}
+
+			/// Subtracts `rhs`, returning `None` on overflow.
+			pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+				self.0.checked_sub(rhs.0).map(Self)
+			}
+
+			/// Subtracts `rhs`, wrapping around at the numeric bound. Wrapping
+			/// preserves evenness.
+			pub fn wrapping_sub(self, rhs: Self) -> Self {
+				Self(self.0.wrapping_sub(rhs.0))
+			}
+
+			/// Multiplies by any integer `rhs`, returning `None` on overflow.
+			pub fn checked_mul(self, rhs: $ty) -> Option<Self> {
+				self.0.checked_mul(rhs).map(Self)
+			}
+
+			/// Multiplies by any integer `rhs`, wrapping around at the numeric bound.
+			/// Wrapping preserves evenness.
+			pub fn wrapping_mul(self, rhs: $ty) -> Self {
+				Self(self.0.wrapping_mul(rhs))
+			}
+		}
+
+		impl<const MIN: i128, const MAX: i128> Bounded<$ty, MIN, MAX> {
+			/// The lower bound clamped to the range of the integer type.
+			const LOWER: $ty = if MIN < <$ty>::MIN as i128 { <$ty>::MIN } else { MIN as $ty };
+			/// The upper bound clamped to the range of the integer type.
+			const UPPER: $ty = if MAX >= 0 && MAX as u128 > <$ty>::MAX as u128 { <$ty>::MAX } else { MAX as $ty };
+
+			/// Adds `rhs`, returning `None` on overflow or if the result is out of
+			/// bounds.
+			pub fn checked_add(self, rhs: $ty) -> Option<Self> {
+				self.0.checked_add(rhs).and_then(Self::new)
+			}
+
+			/// Adds `rhs`, saturating at the bounds.
+			pub fn saturating_add(self, rhs: $ty) -> Self {
+				self.clamped(self.0.saturating_add(rhs))
+			}
+
+			/// Subtracts `rhs`, returning `None` on overflow or if the result is out
+			/// of bounds.
+			pub fn checked_sub(self, rhs: $ty) -> Option<Self> {
+				self.0.checked_sub(rhs).and_then(Self::new)
+			}
+
+			/// Subtracts `rhs`, saturating at the bounds.
+			pub fn saturating_sub(self, rhs: $ty) -> Self {
+				self.clamped(self.0.saturating_sub(rhs))
+			}
+
+			/// Multiplies by `rhs`, returning `None` on overflow or if the result is
+			/// out of bounds.
+			pub fn checked_mul(self, rhs: $ty) -> Option<Self> {
+				self.0.checked_mul(rhs).and_then(Self::new)
+			}
+
+			/// Multiplies by `rhs`, saturating at the bounds.
+			pub fn saturating_mul(self, rhs: $ty) -> Self {
+				self.clamped(self.0.saturating_mul(rhs))
+			}
+
+			fn clamped(self, value: $ty) -> Self {
+				Self(value.clamp(Self::LOWER, Self::UPPER))
+			}
+		}
+		)+
+	};
+}
+
+refinements! { i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize }
+
+impl<T: Copy> Positive<T> {
+	/// Returns the contained value.
+	pub const fn get(self) -> T { self.0 }
+}
+
+impl<T: Copy> Even<T> {
+	/// Returns the contained value.
+	pub const fn get(self) -> T { self.0 }
+}
+
+impl<T: Copy, const MIN: i128, const MAX: i128> Bounded<T, MIN, MAX> {
+	/// Returns the contained value.
+	pub const fn get(self) -> T { self.0 }
+}
+
+impl<T: Display> Display for Positive<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.0.fmt(f) }
+}
+
+impl<T: Display> Display for Even<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.0.fmt(f) }
+}
+
+impl<T: Display, const MIN: i128, const MAX: i128> Display for Bounded<T, MIN, MAX> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { self.0.fmt(f) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type Percent = Bounded<u8, 0, 100>;
+	type Offset = Bounded<i32, -10, 10>;
+
+	#[test]
+	fn positive() {
+		assert_eq!(Positive::new(0u32), None);
+		assert_eq!(Positive::new(-1i8), None);
+
+		let one = Positive::new(1u8).unwrap();
+		let max = Positive::new(u8::MAX).unwrap();
+		assert_eq!(one.checked_sub(one), None);
+		assert_eq!(max.checked_sub(one).map(Positive::get), Some(254));
+		assert_eq!(max.checked_add(one), None);
+		assert_eq!(max.saturating_add(one), max);
+		assert_eq!(max.saturating_mul(max), max);
+
+		let min = Positive::new(1i8).unwrap();
+		assert_eq!(min.checked_sub(Positive::new(i8::MAX).unwrap()), None);
+	}
+
+	#[test]
+	fn even() {
+		assert_eq!(Even::new(3i16), None);
+
+		let two = Even::new(2u8).unwrap();
+		assert_eq!(two.wrapping_mul(128).get(), 0);
+		assert_eq!(two.wrapping_sub(Even::new(4).unwrap()).get(), 254);
+		assert_eq!(two.checked_mul(128), None);
+		assert_eq!(Even::new(i8::MIN).unwrap().checked_sub(Even::new(2).unwrap()), None);
+	}
+
+	#[test]
+	fn bounded() {
+		assert_eq!(Percent::new(101), None);
+		assert_eq!(Offset::new(-11), None);
+		assert_eq!(Offset::new(-10).map(Bounded::get), Some(-10));
+
+		let fifty = Percent::new(50).unwrap();
+		assert_eq!(fifty.checked_add(51), None);
+		assert_eq!(fifty.checked_add(50).map(Bounded::get), Some(100));
+		assert_eq!(fifty.saturating_add(200).get(), 100);
+		assert_eq!(fifty.saturating_sub(51).get(), 0);
+		assert_eq!(fifty.saturating_mul(3).get(), 100);
+
+		let offset = Offset::new(5).unwrap();
+		assert_eq!(offset.saturating_sub(100).get(), -10);
+		assert_eq!(offset.saturating_mul(-3).get(), -10);
+		assert_eq!(offset.saturating_mul(i32::MAX).get(), 10);
+		assert_eq!(offset.checked_mul(-2).map(Bounded::get), Some(-10));
+	}
+
+	#[test]
+	fn bounded_clamps_bounds_to_type() {
+		let wide = Bounded::<i8, { i128::MIN }, { i128::MAX }>::new(i8::MIN).unwrap();
+		assert_eq!(wide.saturating_sub(1).get(), i8::MIN);
+		assert_eq!(wide.saturating_mul(-1).get(), i8::MAX);
+		assert_eq!(wide.saturating_add(i8::MAX).get(), -1);
+	}
+
+	#[test]
+	fn bounded_u128_above_i128() {
+		type Wide = Bounded<u128, 0, { i128::MAX }>;
+		let max = i128::MAX as u128;
+		assert_eq!(Wide::new(max).map(Bounded::get), Some(max));
+		assert_eq!(Wide::new(max + 1), None);
+		assert_eq!(Wide::new(u128::MAX), None);
+		assert_eq!(Wide::new(max).unwrap().checked_add(1), None);
+		assert_eq!(Wide::new(max).unwrap().saturating_add(1).get(), max);
+	}
+}
@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Statistics over iterators of numbers.
+
+use std::cmp::Ordering;
+use super::sealed::{SealedNumExt, SealedSample};
+
+/// A number [`StatsExt`] can compute statistics over: any primitive integer or
+/// float.
+pub trait Sample: SealedSample { }
+
+/// Statistics over an iterator of [`Sample`]s. Integers are summed exactly in a
+/// 256-bit accumulator, floats in `f64`; statistics that aren't whole numbers are
+/// returned as `f64`. Floats are ordered by [`f64::total_cmp`], so `NaN` values
+/// sort after positive infinity, or before negative infinity if negative.
+pub trait StatsExt: Iterator<Item: Sample> + Sized {
+	/// Returns the sum of the values, or `None` if it doesn't fit in the item type.
+	/// Intermediate sums never overflow, only the total is checked. For floats,
+	/// returns `None` if the sum isn't finite. The sum of no values is zero.
+	fn sum_checked(self) -> Option<Self::Item> {
+		Self::Item::narrow(self.fold(Default::default(), |sum, value| value.accumulate(sum)))
+	}
+	/// Returns the arithmetic mean of the values, or `None` if there are none.
+	fn mean(self) -> Option<f64> {
+		let (sum, count) = self.fold((Default::default(), 0usize), |(sum, count), value| {
+			(value.accumulate(sum), count + 1)
+		});
+		(count > 0).then(|| Self::Item::sum_to_f64(sum) / count as f64)
+	}
+	/// Returns the median of the values, the mean of the middle two for an even
+	/// count, or `None` if there are none. Collects the values to sort them.
+	fn median(self) -> Option<f64> {
+		self.percentile(50.0)
+	}
+	/// Returns the most frequent value, the smallest of them on a tie, or `None`
+	/// if there are none. Collects the values to sort them.
+	fn mode(self) -> Option<Self::Item> {
+		let values = sorted(self);
+		let mut mode = (*values.first()?, 0);
+		for run in values.chunk_by(|a, b| a.cmp_total(b).is_eq()) {
+			if run.len() > mode.1 {
+				mode = (run[0], run.len());
+			}
+		}
+		Some(mode.0)
+	}
+	/// Returns the population variance of the values, or `None` if there are
+	/// none. Computed in one pass with Welford's algorithm.
+	fn variance(self) -> Option<f64> {
+		let (mut count, mut mean, mut m2) = (0usize, 0.0, 0.0);
+		for value in self {
+			let value = value.to_f64();
+			count += 1;
+			let delta = value - mean;
+			mean += delta / count as f64;
+			m2 += delta * (value - mean);
+		}
+		(count > 0).then(|| m2 / count as f64)
+	}
+	/// Returns the population standard deviation of the values, or `None` if
+	/// there are none.
+	fn std_dev(self) -> Option<f64> {
+		self.variance().map(f64::sqrt)
+	}
+	/// Returns the smallest and largest values in one pass, or `None` if there are
+	/// none.
+	fn min_max(mut self) -> Option<(Self::Item, Self::Item)> {
+		let first = self.next()?;
+		Some(self.fold((first, first), |(min, max), value| {
+			(
+				if value.cmp_total(&min).is_lt() { value } else { min },
+				if value.cmp_total(&max).is_ge() { value } else { max }
+			)
+		}))
+	}
+	/// Returns the `p`th percentile of the values, interpolating linearly between
+	/// the closest ranks, or `None` if there are none or `p` isn't within
+	/// `0.0..=100.0`. Collects the values to sort them.
+	fn percentile(self, p: f64) -> Option<f64> {
+		if !(0.0..=100.0).contains(&p) {
+			return None
+		}
+
+		let values = sorted(self);
+		let last = values.len().checked_sub(1)?;
+		let rank = p / 100.0 * last as f64;
+		let (lower, fraction) = (rank.floor() as usize, rank.fract());
+		let low = values[lower].to_f64();
+		if fraction == 0.0 {
+			return Some(low)
+		}
+
+		let high = values[lower + 1].to_f64();
+		Some(low * (1.0 - fraction) + high * fraction)
+	}
+}
+
+impl<I: Iterator<Item: Sample>> StatsExt for I { }
+
+fn sorted<I: Iterator<Item: Sample>>(iter: I) -> Vec<I::Item> {
+	let mut values: Vec<_> = iter.collect();
+	values.sort_unstable_by(SealedSample::cmp_total);
+	values
+}
+
+/// A 256-bit two's complement integer, wide enough to sum any number of 128-bit
+/// integers without overflow. It's `pub` only because it's the sealed sum type of
+/// integer [`Sample`]s; this module isn't public and doesn't re-export it, so it
+/// can't be named outside the crate.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Wide {
+	high: i128,
+	low: u128
+}
+
+impl Wide {
+	/// Adds a value given as its sign and sign-extended bits.
+	fn add(self, (negative, bits): (bool, u128)) -> Self {
+		let (low, carry) = self.low.overflowing_add(bits);
+		let high = self.high
+			.wrapping_add(if negative { -1 } else { 0 })
+			.wrapping_add(carry as i128);
+		Self { high, low }
+	}
+
+	/// Converts back to an integer type, if the value fits.
+	fn narrow<T: SealedNumExt>(self) -> Option<T> {
+		let negative = self.high < 0;
+		if self.high != if negative { -1 } else { 0 } {
+			return None
+		}
+
+		let value = T::from_bits(self.low);
+		(value.to_bits() == (negative, self.low)).then_some(value)
+	}
+
+	fn to_f64(self) -> f64 {
+		const LOW_RANGE: f64 = u128::MAX as f64;
+		if self.high < 0 {
+			// Negate to convert the magnitude, rounding it the same as a positive.
+			let (low, carry) = (!self.low).overflowing_add(1);
+			let high = (!self.high).wrapping_add(carry as i128);
+			-(high as f64 * LOW_RANGE + low as f64)
+		} else {
+			self.high as f64 * LOW_RANGE + self.low as f64
+		}
+	}
+}
+
+macro_rules! samples {
+	(ints: $($int:ident)+; floats: $($float:ident)+) => {
+		$(
+		impl Sample for $int { }
+
+		impl SealedSample for $int {
+			type Sum = Wide;
+
+			fn accumulate(self, sum: Wide) -> Wide { sum.add(self.to_bits()) }
+
+			fn narrow(sum: Wide) -> Option<Self> { sum.narrow() }
+
+			fn sum_to_f64(sum: Wide) -> f64 { sum.to_f64() }
+
+			fn to_f64(self) -> f64 { self as f64 }
+
+			fn cmp_total(&self, other: &Self) -> Ordering { self.cmp(other) }
+		}
+		)+
+		$(
+		impl Sample for $float { }
+
+		impl SealedSample for $float {
+			type Sum = f64;
+
+			fn accumulate(self, sum: f64) -> f64 { sum + self as f64 }
+
+			fn narrow(sum: f64) -> Option<Self> {
+				Some(sum as Self).filter(|sum| sum.is_finite())
+			}
+
+			fn sum_to_f64(sum: f64) -> f64 { sum }
+
+			fn to_f64(self) -> f64 { self as f64 }
+
+			fn cmp_total(&self, other: &Self) -> Ordering { self.total_cmp(other) }
+		}
+		)+
+	};
+}
+
+samples! {
+	ints: i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize;
+	floats: f32 f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sum_checked() {
+		assert_eq!([100u8, 155].into_iter().sum_checked(), Some(255));
+		assert_eq!([100u8, 156].into_iter().sum_checked(), None);
+		assert_eq!([i8::MAX, 1, -1].into_iter().sum_checked(), Some(i8::MAX));
+		assert_eq!([i8::MIN, -1, 1].into_iter().sum_checked(), Some(i8::MIN));
+		assert_eq!([i8::MIN, -1].into_iter().sum_checked(), None);
+		assert_eq!([u128::MAX, u128::MAX, 0].into_iter().sum_checked(), None);
+		assert_eq!([u128::MAX; 3].into_iter().sum_checked(), None);
+		assert_eq!([i128::MIN, i128::MIN, i128::MAX, i128::MAX, 1].into_iter().sum_checked(), Some(-1));
+		assert_eq!(std::iter::empty::<u32>().sum_checked(), Some(0));
+		assert_eq!([1.5f32, 2.5].into_iter().sum_checked(), Some(4.0));
+		assert_eq!([f32::MAX, f32::MAX].into_iter().sum_checked(), None);
+		assert_eq!([f64::NAN].into_iter().sum_checked(), None);
+	}
+
+	#[test]
+	fn wide_to_f64() {
+		let sum = |values: &[i128]| values.iter().fold(Wide::default(), |sum, v| v.accumulate(sum));
+		assert_eq!(sum(&[]).to_f64(), 0.0);
+		assert_eq!(sum(&[-5]).to_f64(), -5.0);
+		assert_eq!(sum(&[i128::MIN, i128::MIN]).to_f64(), -(2f64.powi(128)));
+		assert_eq!(sum(&[i128::MAX, i128::MAX, 2]).to_f64(), 2f64.powi(128));
+	}
+
+	#[test]
+	fn mean() {
+		assert_eq!([1u8, 2, 3, 4].into_iter().mean(), Some(2.5));
+		assert_eq!([u64::MAX, u64::MAX].into_iter().mean(), Some(u64::MAX as f64));
+		assert_eq!([i128::MIN, i128::MIN].into_iter().mean(), Some(i128::MIN as f64));
+		assert_eq!([-1i32, 1].into_iter().mean(), Some(0.0));
+		assert_eq!([0.5f32, 1.0].into_iter().mean(), Some(0.75));
+		assert_eq!(std::iter::empty::<i8>().mean(), None);
+	}
+
+	#[test]
+	fn median_and_percentile() {
+		assert_eq!([3u8, 1, 2].into_iter().median(), Some(2.0));
+		assert_eq!([4u8, 1, 3, 2].into_iter().median(), Some(2.5));
+		assert_eq!([7i32].into_iter().median(), Some(7.0));
+		assert_eq!(std::iter::empty::<u8>().median(), None);
+
+		let values = [15u32, 20, 35, 40, 50];
+		assert_eq!(values.into_iter().percentile(0.0), Some(15.0));
+		assert_eq!(values.into_iter().percentile(25.0), Some(20.0));
+		assert_eq!(values.into_iter().percentile(40.0), Some(29.0));
+		assert_eq!(values.into_iter().percentile(100.0), Some(50.0));
+		assert_eq!(values.into_iter().percentile(-1.0), None);
+		assert_eq!(values.into_iter().percentile(100.5), None);
+		assert_eq!(values.into_iter().percentile(f64::NAN), None);
+	}
+
+	#[test]
+	fn mode() {
+		assert_eq!([1u8, 2, 2, 3].into_iter().mode(), Some(2));
+		assert_eq!([3i8, 1, 3, 1, 2].into_iter().mode(), Some(1));
+		assert_eq!([5u32].into_iter().mode(), Some(5));
+		assert_eq!([0.5f64, 0.25, 0.5].into_iter().mode(), Some(0.5));
+		assert_eq!(std::iter::empty::<u8>().mode(), None);
+		// -0.0 and 0.0 are distinct under the total order.
+		assert!([-0.0f64, 0.0, 0.0].into_iter().mode().unwrap().is_sign_positive());
+	}
+
+	#[test]
+	fn variance_and_std_dev() {
+		assert_eq!([2u8, 4, 4, 4, 5, 5, 7, 9].into_iter().variance(), Some(4.0));
+		assert_eq!([2u8, 4, 4, 4, 5, 5, 7, 9].into_iter().std_dev(), Some(2.0));
+		assert_eq!([5i64].into_iter().variance(), Some(0.0));
+		assert_eq!(std::iter::empty::<f32>().std_dev(), None);
+	}
+
+	#[test]
+	fn min_max() {
+		assert_eq!([3i8, -7, 5, 0].into_iter().min_max(), Some((-7, 5)));
+		assert_eq!([1u8].into_iter().min_max(), Some((1, 1)));
+		assert_eq!(std::iter::empty::<u8>().min_max(), None);
+	}
+
+	#[test]
+	fn nan_ordering() {
+		let nan = f64::NAN;
+		let (min, max) = [1.0, nan, f64::INFINITY, -1.0].into_iter().min_max().unwrap();
+		assert_eq!(min, -1.0);
+		assert!(max.is_nan());
+
+		let (min, max) = [1.0, -nan, f64::NEG_INFINITY].into_iter().min_max().unwrap();
+		assert!(min.is_nan() && min.is_sign_negative());
+		assert_eq!(max, 1.0);
+
+		assert_eq!([nan, 1.0, 2.0, 3.0].into_iter().percentile(0.0), Some(1.0));
+		assert!([nan, 1.0, 2.0].into_iter().percentile(100.0).unwrap().is_nan());
+		assert!([nan, nan, 1.0, 2.0].into_iter().median().unwrap().is_nan());
+		assert_eq!([-nan, 1.0, 3.0].into_iter().median(), Some(1.0));
+	}
+}
@@ -4,26 +4,516 @@
 
 #![cfg(feature = "unsafe")]
 
+use std::ffi::{c_char, CStr};
+use std::mem::size_of;
+use std::ops::RangeBounds;
+use std::ptr::NonNull;
+use std::slice;
+
+mod cursor;
+mod tagged;
+
+pub use cursor::RawCursor;
+pub use tagged::TaggedPtr;
+
 mod sealed {
 	pub trait SealedPtr { }
 	impl<T> SealedPtr for *const T { }
 	impl<T> SealedPtr for *mut   T { }
+
+	/// Byte order conversions backing [`Endian`]. The trait can't be named outside
+	/// this crate, but its methods are still callable through an `Endian` bound,
+	/// so they're hidden from the docs instead. They aren't part of the supported
+	/// API and may change in any release.
+	///
+	/// [`Endian`]: super::Endian
+	pub trait SealedEndian: Copy {
+		/// Converts between native and little-endian byte order.
+		#[doc(hidden)]
+		fn swap_le(self) -> Self;
+		/// Converts between native and big-endian byte order.
+		#[doc(hidden)]
+		fn swap_be(self) -> Self;
+	}
 }
 
-pub trait PtrExt: Sized + sealed::SealedPtr {
+/// A primitive number that can be read from and written to raw memory in either
+/// byte order, by [`PtrExt::read_unaligned_le`] and the like. Implemented for all
+/// primitive integers and floats.
+pub trait Endian: sealed::SealedEndian { }
+
+pub trait PtrExt: Copy + sealed::SealedPtr {
+	/// The type pointed to.
+	type Pointee;
+	/// A pointer of the same mutability to `U`.
+	type Cast<U>: PtrExt<Pointee = U>;
+
 	/// Returns `None` if the pointer is null, or wraps it in `Some` if it points
 	/// to a value.
 	fn non_null(self) -> Option<Self>;
+	/// Optionally converts the pointer to [`NonNull`] if it is not null.
+	fn to_non_null(self) -> Option<NonNull<Self::Pointee>>;
+	/// Returns whether the pointer's address is a multiple of `align`, or `None` if
+	/// `align` is not a power of two. Named apart from the unstable inherent
+	/// `is_aligned_to`, which panics instead.
+	fn is_aligned_to_checked(self, align: usize) -> Option<bool>;
+	/// Returns the number of elements the pointer must be offset by to align it to
+	/// `align`, or `None` if `align` is not a power of two or the pointer can't be
+	/// aligned by offsetting it in whole elements. Unlike [`align_offset`][], never
+	/// panics or returns `usize::MAX`.
+	///
+	/// [`align_offset`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.align_offset
+	fn align_offset_checked(self, align: usize) -> Option<usize>;
+	/// Optionally returns the pointer if its address is within `range`'s bounds.
+	fn addr_in<R: RangeBounds<usize>>(self, range: R) -> Option<Self>;
+	/// Returns the distance from `origin` to this pointer in elements, or `None` if
+	/// it isn't a whole number of elements, the pointee is zero-sized, or the
+	/// distance in bytes overflows `isize`. Only addresses are compared, so unlike
+	/// [`offset_from`][], this is safe; the result is only meaningful for pointers
+	/// into the same allocation.
+	///
+	/// [`offset_from`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.offset_from
+	fn offset_from_checked(self, origin: Self) -> Option<isize>;
+	/// Maps the pointer's address with `f`, keeping its provenance, or returns
+	/// `None` if `f` does.
+	fn map_addr_checked(self, f: impl FnOnce(usize) -> Option<usize>) -> Option<Self>;
+	/// Optionally returns a pointer with address `addr` and this pointer's
+	/// provenance, if `addr` is within the `len` bytes starting at this pointer or
+	/// one past their end. This pointer is taken to be the start of an allocation
+	/// of at least `len` bytes, so the new pointer stays in the same allocation.
+	fn with_addr_in_same_allocation(self, addr: usize, len: usize) -> Option<Self>;
+	/// Offsets the pointer forward by `count` bytes, keeping its provenance, or
+	/// returns `None` if the address overflows.
+	fn byte_add_checked(self, count: usize) -> Option<Self>;
+	/// Returns the distance from `origin` to this pointer in bytes, or `None` if it
+	/// overflows `isize`. Like [`offset_from_checked`][], only addresses are
+	/// compared.
+	///
+	/// [`offset_from_checked`]: PtrExt::offset_from_checked
+	fn byte_offset_between(self, origin: Self) -> Option<isize>;
+	/// Optionally casts the pointer to point to `U`, if its address is aligned for
+	/// `U`.
+	fn cast_aligned<U>(self) -> Option<Self::Cast<U>>;
+	/// Borrows a slice of `len` elements starting at the pointer, or an empty slice
+	/// if the pointer is null and `len` is zero. Returns `None` if the pointer is
+	/// null with a non-zero length or misaligned, or if the slice would span more
+	/// than `isize::MAX` bytes or wrap around the address space.
+	///
+	/// # Safety
+	///
+	/// A non-null pointer must be valid for reads of `len` initialized elements,
+	/// which must not be mutated for the lifetime `'a`. See
+	/// [`slice::from_raw_parts`].
+	unsafe fn as_slice_or_empty<'a>(self, len: usize) -> Option<&'a [Self::Pointee]>;
+	/// Borrows the nul-terminated C string at the pointer, or returns `None` if the
+	/// pointer is null.
+	///
+	/// # Safety
+	///
+	/// A non-null pointer must satisfy the requirements of [`CStr::from_ptr`].
+	unsafe fn as_cstr_checked<'a>(self) -> Option<&'a CStr> where Self: PtrExt<Pointee = c_char>;
+	/// Reads a little-endian `U` at the pointer, which needn't be aligned.
+	///
+	/// # Safety
+	///
+	/// The pointer must be valid for reads of `size_of::<U>()` bytes. See
+	/// [`ptr::read_unaligned`].
+	///
+	/// [`ptr::read_unaligned`]: std::ptr::read_unaligned
+	unsafe fn read_unaligned_le<U: Endian>(self) -> U;
+	/// Reads a big-endian `U` at the pointer, which needn't be aligned.
+	///
+	/// # Safety
+	///
+	/// The pointer must be valid for reads of `size_of::<U>()` bytes. See
+	/// [`ptr::read_unaligned`].
+	///
+	/// [`ptr::read_unaligned`]: std::ptr::read_unaligned
+	unsafe fn read_unaligned_be<U: Endian>(self) -> U;
 }
 
-impl<T> PtrExt for *const T {
-	fn non_null(self) -> Option<Self> {
-		self.is_null().then_some(self)
+pub trait PtrMutExt: PtrExt {
+	/// Mutably borrows a slice of `len` elements starting at the pointer, or an
+	/// empty slice if the pointer is null and `len` is zero. Returns `None` in the
+	/// same cases as [`as_slice_or_empty`][].
+	///
+	/// # Safety
+	///
+	/// A non-null pointer must be valid for reads and writes of `len` initialized
+	/// elements, which must not be accessed through any other pointer for the
+	/// lifetime `'a`. See [`slice::from_raw_parts_mut`].
+	///
+	/// [`as_slice_or_empty`]: PtrExt::as_slice_or_empty
+	unsafe fn as_mut_slice_or_empty<'a>(self, len: usize) -> Option<&'a mut [Self::Pointee]>;
+	/// Writes `value` at the pointer in little-endian byte order. The pointer
+	/// needn't be aligned.
+	///
+	/// # Safety
+	///
+	/// The pointer must be valid for writes of `size_of::<U>()` bytes. See
+	/// [`ptr::write_unaligned`].
+	///
+	/// [`ptr::write_unaligned`]: std::ptr::write_unaligned
+	unsafe fn write_unaligned_le<U: Endian>(self, value: U);
+	/// Writes `value` at the pointer in big-endian byte order. The pointer needn't
+	/// be aligned.
+	///
+	/// # Safety
+	///
+	/// The pointer must be valid for writes of `size_of::<U>()` bytes. See
+	/// [`ptr::write_unaligned`].
+	///
+	/// [`ptr::write_unaligned`]: std::ptr::write_unaligned
+	unsafe fn write_unaligned_be<U: Endian>(self, value: U);
+}
+
+macro_rules! ptrs {
+	($($mutability:tt)+) => {
+		$(
+		impl<T> PtrExt for *$mutability T {
+			type Pointee = T;
+			type Cast<U> = *$mutability U;
+
+			fn non_null(self) -> Option<Self> {
+				(!self.is_null()).then_some(self)
+			}
+
+			fn to_non_null(self) -> Option<NonNull<T>> {
+				NonNull::new(self as *mut T)
+			}
+
+			fn is_aligned_to_checked(self, align: usize) -> Option<bool> {
+				align.is_power_of_two().then(|| self.addr() & (align - 1) == 0)
+			}
+
+			fn align_offset_checked(self, align: usize) -> Option<usize> {
+				if !align.is_power_of_two() {
+					return None
+				}
+
+				Some(self.align_offset(align)).filter(|&offset| offset != usize::MAX)
+			}
+
+			fn addr_in<R: RangeBounds<usize>>(self, range: R) -> Option<Self> {
+				range.contains(&self.addr()).then_some(self)
+			}
+
+			fn offset_from_checked(self, origin: Self) -> Option<isize> {
+				let size = isize::try_from(size_of::<T>()).ok().filter(|&size| size > 0)?;
+				let bytes = self.byte_offset_between(origin)?;
+				(bytes % size == 0).then_some(bytes / size)
+			}
+
+			fn map_addr_checked(self, f: impl FnOnce(usize) -> Option<usize>) -> Option<Self> {
+				f(self.addr()).map(|addr| self.with_addr(addr))
+			}
+
+			fn with_addr_in_same_allocation(self, addr: usize, len: usize) -> Option<Self> {
+				let end = self.addr().checked_add(len)?;
+				(self.addr()..=end).contains(&addr).then(|| self.with_addr(addr))
+			}
+
+			fn byte_add_checked(self, count: usize) -> Option<Self> {
+				self.addr().checked_add(count)?;
+				Some(self.wrapping_byte_add(count))
+			}
+
+			fn byte_offset_between(self, origin: Self) -> Option<isize> {
+				isize::try_from(self.addr() as i128 - origin.addr() as i128).ok()
+			}
+
+			fn cast_aligned<U>(self) -> Option<*$mutability U> {
+				let ptr = self.cast::<U>();
+				ptr.is_aligned().then_some(ptr)
+			}
+
+			unsafe fn as_slice_or_empty<'a>(self, len: usize) -> Option<&'a [T]> {
+				let ptr = slice_ptr(self as *mut T, len)?;
+				// SAFETY: the pointer is non-null, aligned, and spans at most
+				// isize::MAX bytes; the caller guarantees it is valid for reads.
+				Some(unsafe { slice::from_raw_parts(ptr, len) })
+			}
+
+			unsafe fn as_cstr_checked<'a>(self) -> Option<&'a CStr> where Self: PtrExt<Pointee = c_char> {
+				let ptr = self.cast::<c_char>().non_null()?;
+				// SAFETY: the pointer is non-null, and the caller guarantees the
+				// rest of CStr::from_ptr's requirements.
+				Some(unsafe { CStr::from_ptr(ptr) })
+			}
+
+			unsafe fn read_unaligned_le<U: Endian>(self) -> U {
+				// SAFETY: the caller guarantees the pointer is valid for reads.
+				unsafe { self.cast::<U>().read_unaligned() }.swap_le()
+			}
+
+			unsafe fn read_unaligned_be<U: Endian>(self) -> U {
+				// SAFETY: the caller guarantees the pointer is valid for reads.
+				unsafe { self.cast::<U>().read_unaligned() }.swap_be()
+			}
+		}
+		)+
+	};
+}
+
+ptrs! { const mut }
+
+impl<T> PtrMutExt for *mut T {
+	unsafe fn as_mut_slice_or_empty<'a>(self, len: usize) -> Option<&'a mut [T]> {
+		let ptr = slice_ptr(self, len)?;
+		// SAFETY: the pointer is non-null, aligned, and spans at most isize::MAX
+		// bytes; the caller guarantees it is valid and unaliased.
+		Some(unsafe { slice::from_raw_parts_mut(ptr, len) })
+	}
+
+	unsafe fn write_unaligned_le<U: Endian>(self, value: U) {
+		// SAFETY: the caller guarantees the pointer is valid for writes.
+		unsafe { self.cast::<U>().write_unaligned(value.swap_le()) }
+	}
+
+	unsafe fn write_unaligned_be<U: Endian>(self, value: U) {
+		// SAFETY: the caller guarantees the pointer is valid for writes.
+		unsafe { self.cast::<U>().write_unaligned(value.swap_be()) }
 	}
 }
 
-impl<T> PtrExt for *mut T {
-	fn non_null(self) -> Option<Self> {
-		self.is_null().then_some(self)
+macro_rules! endians {
+	(ints: $($int:ident)+; floats: $($float:ident)+) => {
+		$(
+		impl Endian for $int { }
+
+		impl sealed::SealedEndian for $int {
+			fn swap_le(self) -> Self { self.to_le() }
+
+			fn swap_be(self) -> Self { self.to_be() }
+		}
+		)+
+		$(
+		impl Endian for $float { }
+
+		impl sealed::SealedEndian for $float {
+			fn swap_le(self) -> Self { Self::from_bits(self.to_bits().to_le()) }
+
+			fn swap_be(self) -> Self { Self::from_bits(self.to_bits().to_be()) }
+		}
+		)+
+	};
+}
+
+endians! {
+	ints: i8 u8 i16 u16 i32 u32 i64 u64 i128 u128 isize usize;
+	floats: f32 f64
+}
+
+/// Validates a pointer to `len` elements for constructing a slice, substituting a
+/// dangling pointer for a null one with zero length.
+fn slice_ptr<T>(ptr: *mut T, len: usize) -> Option<*mut T> {
+	if ptr.is_null() {
+		return (len == 0).then(|| NonNull::dangling().as_ptr())
+	}
+
+	let size = len.checked_mul(size_of::<T>()).filter(|&size| size <= isize::MAX as usize)?;
+	(ptr.is_aligned() && ptr.addr().checked_add(size).is_some()).then_some(ptr)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ffi::{c_char, CStr};
+	use std::ptr::{self, NonNull};
+	use super::{PtrExt, PtrMutExt};
+
+	#[test]
+	fn non_null() {
+		let value = 1u32;
+		let ptr: *const u32 = &value;
+		assert_eq!(ptr.non_null(), Some(ptr));
+		assert_eq!(ptr.cast_mut().non_null(), Some(ptr.cast_mut()));
+		assert_eq!(ptr::null::<u32>().non_null(), None);
+		assert_eq!(ptr::null_mut::<u32>().non_null(), None);
+	}
+
+	#[test]
+	fn to_non_null() {
+		let mut value = 1u32;
+		let ptr: *mut u32 = &mut value;
+		assert_eq!(ptr.to_non_null(), NonNull::new(ptr));
+		assert_eq!(ptr.cast_const().to_non_null(), NonNull::new(ptr));
+		assert_eq!(ptr::null::<u32>().to_non_null(), None);
+		assert_eq!(ptr::null_mut::<u32>().to_non_null(), None);
+	}
+
+	#[test]
+	fn is_aligned_to_checked() {
+		let ptr = ptr::without_provenance::<u8>(0x1000);
+		assert_eq!(ptr.is_aligned_to_checked(1), Some(true));
+		assert_eq!(ptr.is_aligned_to_checked(0x1000), Some(true));
+		assert_eq!(ptr.is_aligned_to_checked(0x2000), Some(false));
+		assert_eq!(ptr.wrapping_add(1).is_aligned_to_checked(2), Some(false));
+		assert_eq!(ptr::without_provenance_mut::<u8>(0x18).is_aligned_to_checked(8), Some(true));
+		assert_eq!(ptr.is_aligned_to_checked(3), None);
+		assert_eq!(ptr.is_aligned_to_checked(0), None);
+	}
+
+	#[test]
+	fn align_offset_checked() {
+		let bytes = [0u8; 16];
+		let ptr = bytes.as_ptr();
+		let offset = ptr.align_offset_checked(8).unwrap();
+		assert_eq!(ptr.wrapping_add(offset).is_aligned_to_checked(8), Some(true));
+		assert!(offset < 8);
+		assert_eq!(ptr.align_offset_checked(1), Some(0));
+		assert_eq!(ptr.align_offset_checked(0), None);
+		assert_eq!(ptr.align_offset_checked(6), None);
+		assert_eq!(ptr.cast_mut().align_offset_checked(12), None);
+	}
+
+	#[test]
+	fn addr_in() {
+		let ptr = ptr::without_provenance::<u8>(0x1000);
+		assert_eq!(ptr.addr_in(0x1000..0x2000), Some(ptr));
+		assert_eq!(ptr.addr_in(..=0x1000), Some(ptr));
+		assert_eq!(ptr.addr_in(0x1001..), None);
+		assert_eq!(ptr.addr_in(..0x1000), None);
+		let ptr = ptr.cast_mut();
+		assert_eq!(ptr.addr_in(..), Some(ptr));
+	}
+
+	#[test]
+	fn offset_from_checked() {
+		let values = [0u32; 8];
+		let start = values.as_ptr();
+		let end = start.wrapping_add(8);
+		assert_eq!(end.offset_from_checked(start), Some(8));
+		assert_eq!(start.offset_from_checked(end), Some(-8));
+		assert_eq!(start.offset_from_checked(start), Some(0));
+		assert_eq!(start.cast::<u8>().wrapping_add(2).cast::<u32>().offset_from_checked(start), None);
+		assert_eq!(end.cast_mut().offset_from_checked(start.cast_mut()), Some(8));
+
+		let unit = ptr::without_provenance::<()>(8);
+		assert_eq!(unit.offset_from_checked(ptr::without_provenance(0)), None);
+
+		let high = ptr::without_provenance::<u8>(usize::MAX);
+		let low = ptr::without_provenance::<u8>(0);
+		assert_eq!(high.offset_from_checked(low), None);
+		assert_eq!(low.offset_from_checked(high), None);
+	}
+
+	#[test]
+	fn as_slice_or_empty() {
+		let values = [1u32, 2, 3];
+		let ptr = values.as_ptr();
+		unsafe {
+			assert_eq!(ptr.as_slice_or_empty(3), Some(&values[..]));
+			assert_eq!(ptr.as_slice_or_empty(0), Some(&[][..]));
+			assert_eq!(ptr::null::<u32>().as_slice_or_empty(0), Some(&[][..]));
+			assert_eq!(ptr::null::<u32>().as_slice_or_empty(1), None);
+			assert_eq!(ptr.cast::<u8>().wrapping_add(1).cast::<u32>().as_slice_or_empty(1), None);
+			assert_eq!(ptr.as_slice_or_empty(usize::MAX / 2), None);
+			assert_eq!(ptr::without_provenance::<u8>(usize::MAX).as_slice_or_empty(2), None);
+		}
+	}
+
+	#[test]
+	fn as_mut_slice_or_empty() {
+		let mut values = [1u32, 2, 3];
+		let ptr = values.as_mut_ptr();
+		unsafe {
+			ptr.as_mut_slice_or_empty(3).unwrap()[1] = 5;
+			assert_eq!(ptr::null_mut::<u32>().as_mut_slice_or_empty(0), Some(&mut [][..]));
+			assert_eq!(ptr::null_mut::<u32>().as_mut_slice_or_empty(2), None);
+			assert_eq!(ptr.as_mut_slice_or_empty(isize::MAX as usize / 4 + 1), None);
+		}
+		assert_eq!(values, [1, 5, 3]);
+	}
+
+	#[test]
+	fn as_cstr_checked() {
+		let string = c"pinion";
+		unsafe {
+			assert_eq!(string.as_ptr().as_cstr_checked(), Some(string));
+			assert_eq!(string.as_ptr().cast_mut().as_cstr_checked(), Some(string));
+			assert_eq!(ptr::null::<c_char>().as_cstr_checked(), None::<&CStr>);
+		}
+	}
+
+	#[test]
+	fn map_addr_checked() {
+		let values = [0u8; 8];
+		let ptr = values.as_ptr();
+		let mapped = ptr.map_addr_checked(|addr| addr.checked_add(3)).unwrap();
+		assert_eq!(mapped, ptr.wrapping_add(3));
+		assert_eq!(unsafe { *mapped }, 0);
+		assert_eq!(ptr.map_addr_checked(|_| None), None);
+		assert_eq!(ptr.cast_mut().map_addr_checked(Some), Some(ptr.cast_mut()));
+	}
+
+	#[test]
+	fn with_addr_in_same_allocation() {
+		let values = [1u8, 2, 3, 4];
+		let ptr = values.as_ptr();
+		let third = ptr.with_addr_in_same_allocation(ptr.addr() + 2, 4).unwrap();
+		assert_eq!(unsafe { *third }, 3);
+		assert_eq!(ptr.with_addr_in_same_allocation(ptr.addr() + 4, 4), Some(ptr.wrapping_add(4)));
+		assert_eq!(ptr.with_addr_in_same_allocation(ptr.addr() + 5, 4), None);
+		assert_eq!(ptr.with_addr_in_same_allocation(ptr.addr() - 1, 4), None);
+		assert_eq!(ptr::without_provenance_mut::<u8>(usize::MAX).with_addr_in_same_allocation(0, 2), None);
+	}
+
+	#[test]
+	fn byte_add_checked() {
+		let values = [1u16, 2];
+		let ptr = values.as_ptr();
+		let second = ptr.byte_add_checked(2).unwrap();
+		assert_eq!(unsafe { *second }, 2);
+		assert_eq!(ptr::without_provenance::<u8>(usize::MAX).byte_add_checked(1), None);
+		assert_eq!(ptr::without_provenance_mut::<u8>(usize::MAX - 1).byte_add_checked(1).map(|ptr| ptr.addr()), Some(usize::MAX));
+	}
+
+	#[test]
+	fn byte_offset_between() {
+		let values = [0u32; 4];
+		let start = values.as_ptr();
+		let end = start.wrapping_add(4);
+		assert_eq!(end.byte_offset_between(start), Some(16));
+		assert_eq!(start.byte_offset_between(end), Some(-16));
+		assert_eq!(end.cast_mut().byte_offset_between(end.cast_mut()), Some(0));
+		let high = ptr::without_provenance::<u8>(usize::MAX);
+		assert_eq!(high.byte_offset_between(ptr::null()), None);
+	}
+
+	#[test]
+	fn cast_aligned() {
+		let mut values = [0u32; 2];
+		let ptr = values.as_mut_ptr().cast::<u8>();
+		let aligned = ptr.cast_aligned::<u32>().unwrap();
+		unsafe { *aligned = 9 };
+		assert_eq!(values[0], 9);
+		assert_eq!(ptr.wrapping_add(1).cast_aligned::<u32>(), None);
+		assert_eq!(ptr.cast_const().wrapping_add(2).cast_aligned::<u16>().map(|ptr| ptr.addr()), Some(ptr.addr() + 2));
+	}
+
+	#[test]
+	fn read_unaligned() {
+		let bytes = [0xFFu8, 0x12, 0x34, 0x56, 0x78];
+		let ptr = bytes.as_ptr().wrapping_add(1);
+		unsafe {
+			assert_eq!(ptr.read_unaligned_le::<u32>(), 0x78563412);
+			assert_eq!(ptr.read_unaligned_be::<u32>(), 0x12345678);
+			assert_eq!(ptr.read_unaligned_be::<i16>(), 0x1234);
+			assert_eq!(bytes.as_ptr().cast_mut().read_unaligned_le::<i8>(), -1);
+		}
+	}
+
+	#[test]
+	fn write_unaligned() {
+		let mut bytes = [0u8; 9];
+		let ptr = bytes.as_mut_ptr().wrapping_add(1);
+		unsafe {
+			ptr.write_unaligned_le(0x0102_0304u32);
+			ptr.wrapping_add(4).write_unaligned_be(0x0506_0708u32);
+			assert_eq!(bytes, [0, 4, 3, 2, 1, 5, 6, 7, 8]);
+			ptr.write_unaligned_be(1.5f64);
+			assert_eq!(ptr.read_unaligned_be::<f64>(), 1.5);
+			assert_eq!(ptr.read_unaligned_le::<u64>().swap_bytes(), 1.5f64.to_bits());
+		}
 	}
 }
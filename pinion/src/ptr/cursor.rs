@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A bounds-checked cursor over raw bytes.
+
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::slice;
+use super::{Endian, PtrExt};
+
+/// A cursor reading numbers and byte slices from a buffer, such as a memory-mapped
+/// file, without copying it. Every read is checked against the end of the buffer,
+/// so reading is safe once the cursor is created.
+#[derive(Copy, Clone, Debug)]
+pub struct RawCursor<'a> {
+	start: *const u8,
+	ptr: *const u8,
+	end: *const u8,
+	_bytes: PhantomData<&'a [u8]>
+}
+
+impl<'a> RawCursor<'a> {
+	/// Creates a cursor at the start of `bytes`.
+	pub fn new(bytes: &'a [u8]) -> Self {
+		let range = bytes.as_ptr_range();
+		Self { start: range.start, ptr: range.start, end: range.end, _bytes: PhantomData }
+	}
+
+	/// Creates a cursor over `len` bytes starting at `ptr`, or returns `None` in
+	/// the same cases as [`PtrExt::as_slice_or_empty`].
+	///
+	/// # Safety
+	///
+	/// `ptr` must satisfy the requirements of [`PtrExt::as_slice_or_empty`] for the
+	/// lifetime `'a`.
+	pub unsafe fn from_raw_parts(ptr: *const u8, len: usize) -> Option<Self> {
+		// SAFETY: the caller guarantees the pointer is valid for `len` bytes.
+		unsafe { ptr.as_slice_or_empty(len) }.map(Self::new)
+	}
+
+	/// Returns the number of bytes read or skipped so far.
+	pub fn position(&self) -> usize {
+		self.ptr.addr() - self.start.addr()
+	}
+
+	/// Returns the number of bytes left to read.
+	pub fn remaining(&self) -> usize {
+		self.end.addr() - self.ptr.addr()
+	}
+
+	/// Returns the bytes left to read, without advancing.
+	pub fn remaining_bytes(&self) -> &'a [u8] {
+		// SAFETY: the cursor is within the buffer it was created from.
+		unsafe { slice::from_raw_parts(self.ptr, self.remaining()) }
+	}
+
+	/// Returns a pointer to the next byte to read.
+	pub fn as_ptr(&self) -> *const u8 { self.ptr }
+
+	/// Advances past `count` bytes and returns them, or returns `None` without
+	/// advancing if fewer remain.
+	pub fn advance(&mut self, count: usize) -> Option<&'a [u8]> {
+		let bytes = self.remaining_bytes().get(..count)?;
+		self.ptr = self.ptr.wrapping_add(count);
+		Some(bytes)
+	}
+
+	/// Reads a little-endian `U` and advances past it, or returns `None` without
+	/// advancing if too few bytes remain.
+	pub fn read_le<U: Endian>(&mut self) -> Option<U> {
+		let bytes = self.advance(size_of::<U>())?;
+		// SAFETY: the bytes are valid for reads of a `U`, and any bit pattern is
+		// a valid primitive number.
+		Some(unsafe { bytes.as_ptr().read_unaligned_le() })
+	}
+
+	/// Reads a big-endian `U` and advances past it, or returns `None` without
+	/// advancing if too few bytes remain.
+	pub fn read_be<U: Endian>(&mut self) -> Option<U> {
+		let bytes = self.advance(size_of::<U>())?;
+		// SAFETY: the bytes are valid for reads of a `U`, and any bit pattern is
+		// a valid primitive number.
+		Some(unsafe { bytes.as_ptr().read_unaligned_be() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ptr;
+	use super::RawCursor;
+
+	#[test]
+	fn read() {
+		let bytes = [1u8, 0x12, 0x34, 0x34, 0x12, 0xAA];
+		let mut cursor = RawCursor::new(&bytes);
+		assert_eq!(cursor.read_le::<u8>(), Some(1));
+		assert_eq!(cursor.read_be::<u16>(), Some(0x1234));
+		assert_eq!(cursor.read_le::<u16>(), Some(0x1234));
+		assert_eq!(cursor.position(), 5);
+		assert_eq!(cursor.read_le::<u16>(), None);
+		assert_eq!(cursor.remaining(), 1);
+		assert_eq!(cursor.read_be::<i8>(), Some(-86));
+		assert_eq!(cursor.read_le::<u8>(), None);
+	}
+
+	#[test]
+	fn advance() {
+		let bytes = *b"header:body";
+		let mut cursor = RawCursor::new(&bytes);
+		assert_eq!(cursor.advance(7), Some(&b"header:"[..]));
+		assert_eq!(cursor.advance(5), None);
+		assert_eq!(cursor.remaining_bytes(), b"body");
+		assert_eq!(cursor.advance(4), Some(&b"body"[..]));
+		assert_eq!(cursor.advance(0), Some(&[][..]));
+		assert_eq!(cursor.as_ptr(), bytes.as_ptr_range().end);
+	}
+
+	#[test]
+	fn from_raw_parts() {
+		let bytes = [1u8, 2];
+		unsafe {
+			let mut cursor = RawCursor::from_raw_parts(bytes.as_ptr(), 2).unwrap();
+			assert_eq!(cursor.read_le::<u16>(), Some(0x0201));
+			assert_eq!(RawCursor::from_raw_parts(ptr::null(), 0).map(|cursor| cursor.remaining()), Some(0));
+			assert!(RawCursor::from_raw_parts(ptr::null(), 1).is_none());
+		}
+	}
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pointers carrying a tag in their low, alignment-guaranteed zero bits.
+
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem::align_of;
+use std::ptr::{self, NonNull};
+
+/// A pointer to `T` with a `BITS`-bit tag packed into its low bits, which are
+/// always zero in a pointer aligned for `T`. Using more bits than `T`'s alignment
+/// leaves free fails to compile.
+///
+/// The tagged pointer has the size of a plain pointer and keeps its provenance,
+/// so it can be stored in an [`AtomicPtr`] through [`as_raw`][] and [`from_raw`][].
+///
+/// [`AtomicPtr`]: std::sync::atomic::AtomicPtr
+/// [`as_raw`]: TaggedPtr::as_raw
+/// [`from_raw`]: TaggedPtr::from_raw
+#[repr(transparent)]
+pub struct TaggedPtr<T, const BITS: usize> {
+	raw: *mut T
+}
+
+impl<T, const BITS: usize> TaggedPtr<T, BITS> {
+	/// The bits holding the tag. Evaluating this checks that `T`'s alignment
+	/// leaves `BITS` low bits free.
+	const MASK: usize = {
+		assert!(BITS as u32 <= align_of::<T>().trailing_zeros(), "the alignment of T must leave BITS low bits free");
+		(1 << BITS) - 1
+	};
+	/// The largest tag.
+	pub const MAX_TAG: usize = Self::MASK;
+
+	/// Optionally tags `ptr` with `tag`, if the pointer is aligned and the tag fits
+	/// in `BITS` bits.
+	pub fn new(ptr: *mut T, tag: usize) -> Option<Self> {
+		if ptr.addr() & Self::MASK != 0 {
+			return None
+		}
+
+		Self { raw: ptr }.with_tag(tag)
+	}
+
+	/// Creates a null pointer with a zero tag.
+	pub fn null() -> Self {
+		Self::from_raw(ptr::null_mut())
+	}
+
+	/// Optionally creates a pointer with a zero tag from `ptr`, if it is aligned.
+	pub fn from_non_null(ptr: NonNull<T>) -> Option<Self> {
+		Self::new(ptr.as_ptr(), 0)
+	}
+
+	/// Creates a tagged pointer from its raw representation, as returned by
+	/// [`as_raw`][].
+	///
+	/// [`as_raw`]: TaggedPtr::as_raw
+	pub fn from_raw(raw: *mut T) -> Self {
+		let _ = Self::MASK;
+		Self { raw }
+	}
+
+	/// Returns the raw representation, the pointer with the tag in its low bits.
+	/// This must not be dereferenced unless the tag is zero.
+	pub fn as_raw(self) -> *mut T { self.raw }
+
+	/// Returns the pointer with the tag cleared.
+	pub fn ptr(self) -> *mut T {
+		self.raw.map_addr(|addr| addr & !Self::MASK)
+	}
+
+	/// Optionally returns the pointer with the tag cleared as [`NonNull`], if it
+	/// is not null.
+	pub fn to_non_null(self) -> Option<NonNull<T>> {
+		NonNull::new(self.ptr())
+	}
+
+	/// Returns the tag.
+	pub fn tag(self) -> usize {
+		self.raw.addr() & Self::MASK
+	}
+
+	/// Optionally replaces the tag with `tag`, if it fits in `BITS` bits.
+	pub fn with_tag(self, tag: usize) -> Option<Self> {
+		if tag & !Self::MASK != 0 {
+			return None
+		}
+
+		Some(Self { raw: self.raw.map_addr(|addr| addr & !Self::MASK | tag) })
+	}
+}
+
+impl<T, const BITS: usize> Clone for TaggedPtr<T, BITS> {
+	fn clone(&self) -> Self { *self }
+}
+
+impl<T, const BITS: usize> Copy for TaggedPtr<T, BITS> { }
+
+impl<T, const BITS: usize> PartialEq for TaggedPtr<T, BITS> {
+	fn eq(&self, other: &Self) -> bool { ptr::eq(self.raw, other.raw) }
+}
+
+impl<T, const BITS: usize> Eq for TaggedPtr<T, BITS> { }
+
+impl<T, const BITS: usize> Hash for TaggedPtr<T, BITS> {
+	fn hash<H: Hasher>(&self, state: &mut H) { self.raw.hash(state) }
+}
+
+impl<T, const BITS: usize> Debug for TaggedPtr<T, BITS> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.debug_struct("TaggedPtr")
+			.field("ptr", &self.ptr())
+			.field("tag", &self.tag())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ptr::{self, NonNull};
+	use super::TaggedPtr;
+
+	#[test]
+	fn tag() {
+		let mut value = 7u64;
+		let ptr: *mut u64 = &mut value;
+		let tagged = TaggedPtr::<u64, 3>::new(ptr, 5).unwrap();
+		assert_eq!(tagged.ptr(), ptr);
+		assert_eq!(tagged.tag(), 5);
+		assert_eq!(tagged.as_raw().addr(), ptr.addr() | 5);
+		assert_eq!(unsafe { *tagged.ptr() }, 7);
+
+		let retagged = tagged.with_tag(2).unwrap();
+		assert_eq!(retagged.ptr(), ptr);
+		assert_eq!(retagged.tag(), 2);
+		assert_eq!(tagged.with_tag(8), None);
+		assert_eq!(TaggedPtr::<u64, 3>::MAX_TAG, 7);
+		assert_eq!(TaggedPtr::<u64, 3>::new(ptr, 8), None);
+		assert_eq!(TaggedPtr::<u64, 3>::new(ptr.wrapping_byte_add(1), 0), None);
+	}
+
+	#[test]
+	fn raw() {
+		let mut value = 1u32;
+		let tagged = TaggedPtr::<u32, 2>::new(&mut value, 3).unwrap();
+		assert_eq!(TaggedPtr::from_raw(tagged.as_raw()), tagged);
+	}
+
+	#[test]
+	fn non_null() {
+		let mut value = 1u16;
+		let ptr = NonNull::from(&mut value);
+		let tagged = TaggedPtr::<u16, 1>::from_non_null(ptr).unwrap();
+		assert_eq!(tagged.tag(), 0);
+		assert_eq!(tagged.with_tag(1).unwrap().to_non_null(), Some(ptr));
+		assert_eq!(TaggedPtr::<u16, 1>::null().to_non_null(), None);
+		assert_eq!(TaggedPtr::<u16, 1>::null().with_tag(1).unwrap().ptr(), ptr::null_mut());
+	}
+}